@@ -0,0 +1,87 @@
+//! Counts the heap allocations made while parsing, and the memory the parsed
+//! value keeps, which timing benchmarks do not show. Run with
+//! `cargo bench --bench alloc`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Wraps the system allocator to count allocations and live bytes.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        LIVE_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        LIVE_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Runs `f`, returning the number of allocations it made and the bytes
+/// still held by its result.
+fn measure<T>(f: impl FnOnce() -> T) -> (usize, usize) {
+    let (allocations, live) = (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        LIVE_BYTES.load(Ordering::Relaxed),
+    );
+    let result = f();
+    let counts = (
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        LIVE_BYTES.load(Ordering::Relaxed) - live,
+    );
+    drop(result);
+    counts
+}
+
+fn records(n: usize) -> String {
+    let records: Vec<String> = (0..n)
+        .map(|i| {
+            format!(
+                r#"{{"id": {i}, "name": "user{i}", "email": "user{i}@example.com", "balance": {}.25, "active": {}}}"#,
+                i * 7,
+                i % 2 == 0
+            )
+        })
+        .collect();
+    format!("[{}]", records.join(",\n"))
+}
+
+fn report(name: &str, (allocations, retained): (usize, usize)) {
+    println!("{name:<32} {allocations:>10} allocations {retained:>12} bytes retained");
+}
+
+fn main() {
+    let records = records(10_000);
+    let interned = rj::ParseOptions {
+        intern_keys: true,
+        ..Default::default()
+    };
+    report("records_10k", measure(|| rj::try_parse(&records)));
+    report(
+        "records_10k intern_keys",
+        measure(|| rj::try_parse_with(&records, &interned)),
+    );
+    report(
+        "records_10k borrowed",
+        measure(|| rj::parse_borrowed(&records)),
+    );
+}
@@ -0,0 +1,118 @@
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+
+fn small_object() -> String {
+    r#"{"id": 1, "name": "rj", "active": true, "tags": ["json", "cli"], "score": 0.5}"#.to_string()
+}
+
+fn records(n: usize) -> String {
+    let records: Vec<String> = (0..n)
+        .map(|i| {
+            format!(
+                r#"{{"id": {i}, "name": "user{i}", "email": "user{i}@example.com", "balance": {}.25, "active": {}}}"#,
+                i * 7,
+                i % 2 == 0
+            )
+        })
+        .collect();
+    format!("[{}]", records.join(",\n"))
+}
+
+fn deeply_nested(depth: usize) -> String {
+    format!("{}1{}", r#"{"a": ["#.repeat(depth), "]}".repeat(depth))
+}
+
+/// Strings dominated by characters that must be escaped on output.
+fn escaped_strings(n: usize) -> String {
+    let s = r#""line\nbreak \"quoted\" tab\t back\\slash éあ 😀""#;
+    format!("[{}]", vec![s; n].join(","))
+}
+
+fn documents() -> Vec<(&'static str, String)> {
+    vec![
+        ("small_object", small_object()),
+        ("records_10k", records(10_000)),
+        ("nested_500", deeply_nested(500)),
+        ("escaped_strings", escaped_strings(1_000)),
+    ]
+}
+
+/// Options without a nesting limit, which `nested_500` exceeds.
+fn unlimited() -> rj::ParseOptions {
+    rj::ParseOptions {
+        max_depth: usize::MAX,
+        ..Default::default()
+    }
+}
+
+fn parse(c: &mut Criterion) {
+    let options = unlimited();
+    let mut group = c.benchmark_group("parse");
+    for (name, json) in documents() {
+        group.bench_function(name, |b| {
+            b.iter(|| rj::try_parse_with(black_box(&json), &options))
+        });
+    }
+    group.finish();
+}
+
+/// Many small messages with escapes, parsed with a fresh parser per call
+/// versus one [`rj::Parser`] whose scratch buffer is reused.
+fn repeated(c: &mut Criterion) {
+    let msg =
+        r#"{"event": "login", "user": "a\"b\"c", "path": "C:\\Users\\rj", "note": "line\nbreak"}"#;
+    let mut group = c.benchmark_group("repeated_small_messages");
+    group.bench_function("try_parse", |b| b.iter(|| rj::try_parse(black_box(msg))));
+    let mut parser = rj::Parser::new();
+    group.bench_function("parser_reused", |b| b.iter(|| parser.parse(black_box(msg))));
+    group.finish();
+}
+
+/// Parsing records whose keys repeat, with and without interning them.
+fn interned_keys(c: &mut Criterion) {
+    let json = records(10_000);
+    let interned = rj::ParseOptions {
+        intern_keys: true,
+        ..Default::default()
+    };
+    let mut group = c.benchmark_group("records_10k_keys");
+    group.bench_function("owned", |b| b.iter(|| rj::try_parse(black_box(&json))));
+    group.bench_function("interned", |b| {
+        b.iter(|| rj::try_parse_with(black_box(&json), &interned))
+    });
+    group.bench_function("borrowed", |b| {
+        b.iter(|| rj::parse_borrowed(black_box(&json)))
+    });
+    group.finish();
+}
+
+fn serialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("to_compact_string");
+    for (name, json) in documents() {
+        let value = rj::try_parse_with(&json, &unlimited()).unwrap();
+        group.bench_function(name, |b| b.iter(|| black_box(&value).to_compact_string()));
+    }
+    group.finish();
+}
+
+/// A large array serialized through `Display`, which grows its `String` as
+/// it goes, versus `to_compact_string`, which preallocates from
+/// `serialized_len_estimate`.
+fn preallocated(c: &mut Criterion) {
+    let value = rj::parse(&records(10_000));
+    let mut group = c.benchmark_group("serialize_records_10k");
+    group.bench_function("display", |b| b.iter(|| black_box(&value).to_string()));
+    group.bench_function("preallocated", |b| {
+        b.iter(|| black_box(&value).to_compact_string())
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    parse,
+    repeated,
+    interned_keys,
+    serialize,
+    preallocated
+);
+criterion_main!(benches);
@@ -0,0 +1,24 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Whatever parses must serialize back to JSON that parses to the same value.
+// Numbers beyond the range of `f64` would read as infinity, which is written
+// as `null`, so they are rejected up front.
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let options = rj::ParseOptions {
+        error_on_number_overflow: true,
+        ..Default::default()
+    };
+    let Ok(value) = rj::try_parse_with(input, &options) else {
+        return;
+    };
+    for json in [value.to_compact_string(), rj::stringify_pretty(&value)] {
+        let reparsed = rj::try_parse_with(&json, &options)
+            .unwrap_or_else(|e| panic!("{json:?} does not parse back: {e}"));
+        assert_eq!(reparsed, value);
+    }
+});
@@ -1,30 +1,336 @@
-use std::io::{Read, stdin};
+use std::io::{BufRead, BufReader, IsTerminal, Read, stdin, stdout};
+
+fn file_arg() -> clap::Arg {
+    clap::Arg::new("file")
+        .short('f')
+        .long("file")
+        .value_name("PATH")
+}
+
+fn color_arg() -> clap::Arg {
+    clap::Arg::new("color")
+        .long("color")
+        .value_name("WHEN")
+        .help("Highlight pretty output")
+        .value_parser(["auto", "always", "never"])
+        .default_value("auto")
+}
+
+fn indent_arg() -> clap::Arg {
+    clap::Arg::new("indent")
+        .long("indent")
+        .value_name("N")
+        .help("Number of spaces per nesting level [default: 2]")
+        .value_parser(clap::value_parser!(usize))
+}
+
+fn tabs_arg() -> clap::Arg {
+    clap::Arg::new("tabs")
+        .long("tabs")
+        .help("Indent with one tab per nesting level")
+        .conflicts_with("indent")
+        .action(clap::ArgAction::SetTrue)
+}
+
+/// Whether to color output, where `auto` means only when writing to a terminal.
+fn use_color(m: &clap::ArgMatches) -> bool {
+    match m.get_one::<String>("color").map(String::as_str) {
+        Some("always") => true,
+        Some("auto") => stdout().is_terminal(),
+        _ => false,
+    }
+}
+
+fn read_input(m: &clap::ArgMatches) -> String {
+    if let Some(path) = m.get_one::<String>("file") {
+        return std::fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("rj: {path}: {e}");
+            std::process::exit(1);
+        });
+    }
+    m.try_get_one("json")
+        .ok()
+        .flatten()
+        .map(|x: &String| x.to_string())
+        .unwrap_or_else(|| {
+            let mut buf = Vec::new();
+            let mut handle = stdin().lock();
+            let _ = handle.read_to_end(&mut buf);
+            String::from_utf8_lossy(&buf).to_string()
+        })
+}
+
+fn parse_or_exit(json: &str) -> rj::Value {
+    rj::try_parse(json).unwrap_or_else(|e| {
+        eprintln!("rj: {e}");
+        std::process::exit(1);
+    })
+}
+
+/// Prints the value at `ptr`, or exits with an error if there is none.
+fn print_pointer(parsed: &rj::Value, ptr: &str, raw: bool) {
+    match parsed.pointer(ptr) {
+        Some(rj::Value::String(s)) if raw => println!("{s}"),
+        Some(v) => println!("{}", v.to_compact_string()),
+        None => {
+            eprintln!("rj: no value at pointer '{ptr}'");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Formats the file at `path` and replaces it by renaming a temporary file
+/// over it, so that a crash never leaves it half written. Invalid JSON is
+/// reported and the file left as it is.
+fn format_in_place(path: &str, options: &rj::FormatOptions) {
+    let fail = |e: std::io::Error| -> ! {
+        eprintln!("rj: {path}: {e}");
+        std::process::exit(1);
+    };
+    let json = std::fs::read_to_string(path).unwrap_or_else(|e| fail(e));
+    let parsed = parse_or_exit(&json);
+    let formatted = rj::stringify_pretty_with(&parsed, options) + "\n";
+
+    let path = std::path::Path::new(path);
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = path.with_file_name(format!(".{name}.rj-tmp"));
+    let written = std::fs::write(&tmp, formatted)
+        .and_then(|()| std::fs::metadata(path))
+        .and_then(|meta| std::fs::set_permissions(&tmp, meta.permissions()))
+        .and_then(|()| std::fs::rename(&tmp, path));
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp);
+        fail(e);
+    }
+}
+
+fn format_options(m: &clap::ArgMatches) -> rj::FormatOptions {
+    rj::FormatOptions {
+        indent: m.get_one::<usize>("indent").copied().unwrap_or(2),
+        use_tabs: m.get_flag("tabs"),
+        color: use_color(m),
+        ..Default::default()
+    }
+}
+
+/// Parses and prints one JSON value per input line, reading the input
+/// incrementally so that large logs are never held in memory at once.
+///
+/// Stops at the first malformed line unless `--keep-going` is given, in which
+/// case errors are reported as they occur and summarized at the end.
+fn ndjson(m: &clap::ArgMatches) {
+    let reader: Box<dyn BufRead> = match m.get_one::<String>("file") {
+        Some(path) => {
+            let file = std::fs::File::open(path).unwrap_or_else(|e| {
+                eprintln!("rj: {path}: {e}");
+                std::process::exit(1);
+            });
+            Box::new(BufReader::new(file))
+        }
+        None => Box::new(stdin().lock()),
+    };
+
+    let options = format_options(m);
+    let (mut records, mut failed) = (0, 0);
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.unwrap_or_else(|e| {
+            eprintln!("rj: {e}");
+            std::process::exit(1);
+        });
+        if line.trim().is_empty() {
+            continue;
+        }
+        records += 1;
+        let parsed = match rj::try_parse(&line) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("rj: line {}, column {}: {}", i + 1, e.column(), e.message());
+                if !m.get_flag("keep_going") {
+                    std::process::exit(1);
+                }
+                failed += 1;
+                continue;
+            }
+        };
+        if m.get_flag("validate") {
+            continue;
+        }
+        if m.get_flag("pretty") {
+            println!("{}", parsed.to_pretty_string_with(&options));
+        } else {
+            println!("{}", parsed.to_compact_string());
+        }
+    }
+    if failed > 0 {
+        eprintln!("rj: {failed} of {records} records failed");
+        std::process::exit(1);
+    }
+}
 
 fn main() {
     #[rustfmt::skip]
     let cmd = clap::Command::new("rj")
+        .args_conflicts_with_subcommands(true)
         .arg(clap::Arg::new("json"))
+        .arg(file_arg().conflicts_with("json"))
         .arg(clap::Arg::new("pretty")
             .short('p')
             .long("pretty")
             .action(clap::ArgAction::SetTrue),
+        )
+        .arg(indent_arg().requires("pretty"))
+        .arg(tabs_arg().requires("pretty"))
+        .arg(color_arg())
+        .arg(clap::Arg::new("compact")
+            .short('c')
+            .long("compact")
+            .visible_alias("minify")
+            .conflicts_with("pretty")
+            .action(clap::ArgAction::SetTrue),
+        )
+        .arg(clap::Arg::new("validate")
+            .long("validate")
+            .help("Only check that the input is valid JSON")
+            .conflicts_with_all(["pretty", "compact"])
+            .action(clap::ArgAction::SetTrue),
+        )
+        .arg(clap::Arg::new("get")
+            .long("get")
+            .value_name("POINTER")
+            .help("Print only the value at a JSON Pointer such as /a/0")
+            .conflicts_with_all(["pretty", "validate"]),
+        )
+        .arg(clap::Arg::new("raw")
+            .long("raw")
+            .help("Print strings selected by --get without quotes")
+            .requires("get")
+            .action(clap::ArgAction::SetTrue),
+        )
+        .arg(clap::Arg::new("debug")
+            .long("debug")
+            .help("Print the parsed value as a Rust debug dump instead of JSON")
+            .conflicts_with_all(["pretty", "compact", "validate", "get", "ndjson"])
+            .action(clap::ArgAction::SetTrue),
+        )
+        .arg(clap::Arg::new("ndjson")
+            .long("ndjson")
+            .help("Treat each input line as a separate JSON value")
+            .conflicts_with_all(["json", "get"])
+            .action(clap::ArgAction::SetTrue),
+        )
+        .arg(clap::Arg::new("keep_going")
+            .long("keep-going")
+            .help("Report malformed --ndjson lines and carry on with the rest")
+            .requires("ndjson")
+            .action(clap::ArgAction::SetTrue),
+        )
+        .subcommand(clap::Command::new("format")
+            .about("Print the input as indented JSON")
+            .arg(file_arg())
+            .arg(clap::Arg::new("in_place")
+                .short('i')
+                .long("in-place")
+                .value_name("PATH")
+                .help("Format a file and write it back instead of printing it")
+                .conflicts_with_all(["file", "color"]),
+            )
+            .arg(indent_arg())
+            .arg(tabs_arg())
+            .arg(color_arg()),
+        )
+        .subcommand(clap::Command::new("minify")
+            .about("Print the input as compact JSON")
+            .arg(file_arg()),
+        )
+        .subcommand(clap::Command::new("validate")
+            .about("Only check that the input is valid JSON")
+            .arg(file_arg()),
+        )
+        .subcommand(clap::Command::new("get")
+            .about("Print the value at a JSON Pointer such as /a/0")
+            .arg(clap::Arg::new("pointer")
+                .value_name("POINTER")
+                .required(true),
+            )
+            .arg(file_arg())
+            .arg(clap::Arg::new("raw")
+                .long("raw")
+                .help("Print strings without quotes")
+                .action(clap::ArgAction::SetTrue),
+            ),
+        )
+        .subcommand(clap::Command::new("split")
+            .about("Print each concatenated JSON document on its own line")
+            .arg(file_arg()),
         );
 
     let m = cmd.try_get_matches().unwrap_or_else(|e| e.exit());
-    let json: String = m
-        .get_one("json")
-        .map(|x: &String| x.to_string())
-        .unwrap_or_else(|| {
-            let mut buf = Vec::new();
-            let mut handle = stdin().lock();
-            let _ = handle.read_to_end(&mut buf);
-            String::from_utf8_lossy(&buf).to_string()
-        });
+    match m.subcommand() {
+        Some(("split", sub)) => {
+            let json = read_input(sub);
+            match rj::split_documents(&json) {
+                Ok(docs) => docs.iter().for_each(|doc| println!("{doc}")),
+                Err(e) => {
+                    eprintln!("rj: {e}");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        Some(("format", sub)) => {
+            if let Some(path) = sub.get_one::<String>("in_place") {
+                let options = rj::FormatOptions {
+                    color: false,
+                    ..format_options(sub)
+                };
+                format_in_place(path, &options);
+                return;
+            }
+            let parsed = parse_or_exit(&read_input(sub));
+            println!(
+                "{}",
+                rj::stringify_pretty_with(&parsed, &format_options(sub))
+            );
+            return;
+        }
+        Some(("minify", sub)) => {
+            let parsed = parse_or_exit(&read_input(sub));
+            println!("{}", parsed.to_compact_string());
+            return;
+        }
+        Some(("validate", sub)) => {
+            parse_or_exit(&read_input(sub));
+            return;
+        }
+        Some(("get", sub)) => {
+            let parsed = parse_or_exit(&read_input(sub));
+            let ptr = sub.get_one::<String>("pointer").unwrap();
+            print_pointer(&parsed, ptr, sub.get_flag("raw"));
+            return;
+        }
+        _ => {}
+    }
+
+    if m.get_flag("ndjson") {
+        ndjson(&m);
+        return;
+    }
+
+    let parsed = parse_or_exit(&read_input(&m));
+    if m.get_flag("validate") {
+        return;
+    }
+    if let Some(ptr) = m.get_one::<String>("get") {
+        print_pointer(&parsed, ptr, m.get_flag("raw"));
+        return;
+    }
     if m.get_flag("pretty") {
-        let formatted = rj::format(&json);
+        let formatted = rj::stringify_pretty_with(&parsed, &format_options(&m));
         println!("{formatted}");
-    } else {
-        let parsed = rj::parse(&json);
+    } else if m.get_flag("debug") {
         println!("{:#?}", parsed);
+    } else {
+        let compact = parsed.to_compact_string();
+        println!("{compact}");
     }
 }
@@ -0,0 +1,303 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Output, Stdio};
+
+fn rj(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rj-cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // The command may exit before reading its input, e.g. on a usage error.
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn invalid_input_exits_with_one() {
+    let out = rj(&[], "x\n");
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert_eq!(stderr, "rj: line 1, column 1: Unexpected token: 'x'\n");
+    assert!(out.stdout.is_empty());
+}
+
+#[test]
+fn valid_input_exits_with_zero() {
+    let out = rj(&["-c"], r#"{ "a" : 1 }"#);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "{\"a\":1}\n");
+}
+
+#[test]
+fn default_output_is_compact_json() {
+    let out = rj(&[], "[ 1, { \"a\" : null } ]");
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "[1,{\"a\":null}]\n");
+
+    let out = rj(&["--debug"], "[true]");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "Array(\n    [\n        Boolean(\n            true,\n        ),\n    ],\n)\n"
+    );
+}
+
+#[test]
+fn debug_dumps_object() {
+    let out = rj(&["--debug"], r#"{"a": 1}"#);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "Object(\n    {\n        \"a\": Number(\n            1.0,\n        ),\n    },\n)\n"
+    );
+
+    let out = rj(&["--debug", "--pretty"], "[]");
+    assert_eq!(out.status.code(), Some(2));
+
+    let out = rj(&["--debug", "--ndjson"], "[]\n");
+    assert_eq!(out.status.code(), Some(2));
+    assert!(out.stdout.is_empty());
+}
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn validate_valid_file() {
+    let out = rj(&["--validate", "--file", &fixture("valid.json")], "");
+    assert_eq!(out.status.code(), Some(0));
+    assert!(out.stdout.is_empty());
+    assert!(out.stderr.is_empty());
+}
+
+#[test]
+fn validate_invalid_file() {
+    let out = rj(&["--validate", "--file", &fixture("invalid.json")], "");
+    assert_eq!(out.status.code(), Some(1));
+    assert!(out.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8(out.stderr).unwrap(),
+        "rj: line 3, column 19: array must end with ']'\n"
+    );
+}
+
+#[test]
+fn get_pointer() {
+    let json = r#"{"Image": {"Thumbnail": {"Url": "http://example.com"}, "IDs": [1, 2]}}"#;
+    let out = rj(&["--get", "/Image/IDs"], json);
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "[1,2]\n");
+
+    let out = rj(&["--get", "/Image/Thumbnail/Url", "--raw"], json);
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "http://example.com\n"
+    );
+
+    let out = rj(&["--get", "/Image/Missing"], json);
+    assert_eq!(out.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(out.stderr).unwrap(),
+        "rj: no value at pointer '/Image/Missing'\n"
+    );
+}
+
+#[test]
+fn ndjson_stops_at_invalid_line() {
+    let out = rj(&["--ndjson", "--file", &fixture("records.jsonl")], "");
+    assert_eq!(out.status.code(), Some(1));
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "{\"id\":1}\n");
+    assert_eq!(
+        String::from_utf8(out.stderr).unwrap(),
+        "rj: line 2, column 10: String must start with '\"'. Found '}'.\n"
+    );
+}
+
+#[test]
+fn ndjson_keep_going() {
+    let out = rj(
+        &[
+            "--ndjson",
+            "--keep-going",
+            "--file",
+            &fixture("records.jsonl"),
+        ],
+        "",
+    );
+    assert_eq!(out.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "{\"id\":1}\n{\"id\":3}\n"
+    );
+    assert_eq!(
+        String::from_utf8(out.stderr).unwrap(),
+        "rj: line 2, column 10: String must start with '\"'. Found '}'.\n\
+         rj: 1 of 3 records failed\n"
+    );
+
+    let out = rj(&["--ndjson", "--keep-going"], "1\n2\n");
+    assert_eq!(out.status.code(), Some(0));
+    assert!(out.stderr.is_empty());
+
+    let out = rj(&["--keep-going"], "1");
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn ndjson_prints_each_record_before_reading_the_next() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rj-cli"))
+        .arg("--ndjson")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    // Each record comes back while stdin is still open.
+    for record in ["{ \"id\": 1 }", "[ 2 ]"] {
+        writeln!(stdin, "{record}").unwrap();
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+        assert_eq!(line, rj::parse(record).to_compact_string() + "\n");
+    }
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn ndjson_from_stdin() {
+    let out = rj(&["--ndjson"], "{ \"a\": 1 }\n\n[ 2 ]\n");
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "{\"a\":1}\n[2]\n");
+}
+
+#[test]
+fn format_subcommand() {
+    let out = rj(&["format"], r#"{"a": [1]}"#);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "{\n  \"a\": [\n    1\n  ]\n}\n"
+    );
+
+    let out = rj(&["format", "--indent", "4"], "[true]");
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "[\n    true\n]\n");
+
+    let out = rj(&["format", "--tabs"], "[null]");
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "[\n\tnull\n]\n");
+
+    let out = rj(&["format", "--indent", "-1"], "[]");
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn format_in_place() {
+    let path = format!("{}/in_place.json", env!("CARGO_TARGET_TMPDIR"));
+    std::fs::copy(fixture("messy.json"), &path).unwrap();
+    let out = rj(&["format", "--in-place", &path, "--indent", "4"], "");
+    assert_eq!(out.status.code(), Some(0));
+    assert!(out.stdout.is_empty());
+
+    let formatted = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        formatted,
+        "{\n    \"name\": \"rj\",\n    \"tags\": [\n        \"json\",\n        \"cli\"\n    ],\n    \
+         \"nested\": {\n        \"a\": [\n            1,\n            2,\n            {}\n        ]\n    \
+         },\n    \"empty\": []\n}\n"
+    );
+    assert_eq!(
+        rj::try_parse(&formatted),
+        rj::try_parse(&std::fs::read_to_string(fixture("messy.json")).unwrap())
+    );
+
+    // Invalid input leaves the file untouched.
+    std::fs::copy(fixture("invalid.json"), &path).unwrap();
+    let out = rj(&["format", "-i", &path], "");
+    assert_eq!(out.status.code(), Some(1));
+    assert_eq!(
+        std::fs::read(&path).unwrap(),
+        std::fs::read(fixture("invalid.json")).unwrap()
+    );
+
+    let out = rj(&["format", "-i", &format!("{path}.missing")], "");
+    assert_eq!(out.status.code(), Some(1));
+}
+
+#[test]
+fn pretty_indent_option() {
+    let out = rj(&["--pretty", "--indent", "4"], r#"{"a": [1]}"#);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "{\n    \"a\": [\n        1\n    ]\n}\n"
+    );
+
+    let out = rj(&["-p", "--tabs"], "[null]");
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "[\n\tnull\n]\n");
+
+    for bad in ["-1", "four"] {
+        let out = rj(&["--pretty", "--indent", bad], "[]");
+        assert_eq!(out.status.code(), Some(2), "--indent {bad}");
+    }
+    // Indentation only applies to pretty output.
+    let out = rj(&["--indent", "4"], "[]");
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn minify_subcommand() {
+    let out = rj(&["minify", "--file", &fixture("valid.json")], "");
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(!stdout.trim_end().contains(['\n', ' ']), "{stdout}");
+    assert!(rj::try_parse(&stdout).is_ok());
+}
+
+#[test]
+fn validate_subcommand() {
+    let out = rj(&["validate", "--file", &fixture("valid.json")], "");
+    assert_eq!(out.status.code(), Some(0));
+    assert!(out.stdout.is_empty());
+
+    let out = rj(&["validate"], "[1,");
+    assert_eq!(out.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(out.stderr).unwrap(),
+        "rj: line 1, column 4: Unexpected end of input.\n"
+    );
+}
+
+#[test]
+fn get_subcommand() {
+    let json = r#"{"a": {"b": ["x", "y"]}}"#;
+    let out = rj(&["get", "/a/b/1", "--raw"], json);
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "y\n");
+
+    let out = rj(&["get", "/a"], json);
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "{\"b\":[\"x\",\"y\"]}\n"
+    );
+
+    let out = rj(&["get", "/c"], json);
+    assert_eq!(out.status.code(), Some(1));
+}
+
+#[test]
+fn color_option() {
+    let json = r#"{"a": [1, "x", null]}"#;
+    let plain = rj(&["-p"], json).stdout;
+    assert_eq!(rj(&["-p", "--color", "never"], json).stdout, plain);
+    assert_eq!(rj(&["format", "--color", "never"], json).stdout, plain);
+    // Not a terminal, so `auto` leaves the output uncolored.
+    assert_eq!(rj(&["-p", "--color", "auto"], json).stdout, plain);
+
+    let colored = String::from_utf8(rj(&["-p", "--color", "always"], json).stdout).unwrap();
+    assert!(colored.contains("\x1b[1;34m\"a\"\x1b[0m"), "{colored}");
+    assert_eq!(colored.matches("\x1b[0m").count(), 4);
+
+    let out = rj(&["-p", "--color", "sometimes"], json);
+    assert_eq!(out.status.code(), Some(2));
+}
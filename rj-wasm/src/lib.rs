@@ -5,13 +5,93 @@ extern "C" {
     pub fn alert(s: &str);
 }
 
+/// Turns a parse error into a JS `Error` whose message carries the position.
+fn to_js_error(e: rj::ParseError) -> JsValue {
+    JsError::new(&e.to_string()).into()
+}
+
+/// Converts a parsed value into the equivalent JS value.
+///
+/// Members are defined as own enumerable properties, as `JSON.parse` does,
+/// so a `"__proto__"` key is an ordinary property and never replaces the
+/// prototype of the object.
+fn to_js(value: &rj::Value) -> Result<JsValue, JsValue> {
+    let v = match value {
+        rj::Value::String(s) => JsValue::from_str(s),
+        rj::Value::Number(n) => JsValue::from_f64(*n),
+        rj::Value::RawNumber(s) => JsValue::from_f64(s.parse().unwrap_or(f64::NAN)),
+        rj::Value::Boolean(b) => JsValue::from_bool(*b),
+        rj::Value::Null => JsValue::NULL,
+        rj::Value::Object(obj) => {
+            let o = js_sys::Object::new();
+            for (k, v) in obj {
+                js_sys::Object::define_property(
+                    &o,
+                    &JsValue::from_str(k),
+                    &data_property(&to_js(v)?)?,
+                );
+            }
+            o.into()
+        }
+        rj::Value::Array(arr) => {
+            let a = js_sys::Array::new();
+            for v in arr {
+                a.push(&to_js(v)?);
+            }
+            a.into()
+        }
+    };
+    Ok(v)
+}
+
+/// A descriptor for a writable, enumerable and configurable data property
+/// holding `value`, like the ones `JSON.parse` creates.
+fn data_property(value: &JsValue) -> Result<js_sys::Object, JsValue> {
+    let descriptor = js_sys::Object::new();
+    js_sys::Reflect::set(&descriptor, &"value".into(), value)?;
+    for flag in ["writable", "enumerable", "configurable"] {
+        js_sys::Reflect::set(&descriptor, &flag.into(), &JsValue::TRUE)?;
+    }
+    Ok(descriptor)
+}
+
+#[wasm_bindgen]
+pub fn format(input: &str) -> Result<String, JsValue> {
+    let parsed = rj::try_parse(input).map_err(to_js_error)?;
+    Ok(rj::stringify_pretty(&parsed))
+}
+
+/// Re-serializes `input` as compact JSON.
+#[wasm_bindgen]
+pub fn minify(input: &str) -> Result<String, JsValue> {
+    let parsed = rj::try_parse(input).map_err(to_js_error)?;
+    Ok(parsed.to_compact_string())
+}
+
+/// Parses `input` into plain JS objects, arrays and primitives.
+/// As with `JSON.parse`, a `"__proto__"` member becomes an own property.
+#[wasm_bindgen]
+pub fn parse(input: &str) -> Result<JsValue, JsValue> {
+    let parsed = rj::try_parse(input).map_err(to_js_error)?;
+    to_js(&parsed)
+}
+
+/// Parses `input` and returns the Rust debug representation of the value,
+/// for troubleshooting the parser rather than as output.
 #[wasm_bindgen]
-pub fn format(input: &str) -> String {
-    rj::format(input)
+pub fn parse_debug(input: &str) -> Result<String, JsValue> {
+    let parsed = rj::try_parse(input).map_err(to_js_error)?;
+    Ok(format!("{:#?}", parsed))
 }
 
+/// Like `format`, indenting with `indent` spaces or with tabs.
 #[wasm_bindgen]
-pub fn parse(input: &str) -> String {
-    let parsed = rj::parse(input);
-    format!("{:#?}", parsed)
+pub fn format_with_indent(input: &str, indent: u32, use_tabs: bool) -> Result<String, JsValue> {
+    let parsed = rj::try_parse(input).map_err(to_js_error)?;
+    let options = rj::FormatOptions {
+        indent: indent as usize,
+        use_tabs,
+        ..Default::default()
+    };
+    Ok(rj::stringify_pretty_with(&parsed, &options))
 }
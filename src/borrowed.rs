@@ -0,0 +1,165 @@
+use std::borrow::Cow;
+
+use crate::{Map, Parser, Value, parse::Tree};
+
+/// A JSON value that borrows from the text it was parsed from, see
+/// [`parse_borrowed`](crate::parse_borrowed).
+///
+/// Strings and keys without escapes are slices of the input, so parsing
+/// allocates only for containers and escaped strings. Object members keep
+/// their source order, including every occurrence of a repeated key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BorrowedValue<'a> {
+    String(Cow<'a, str>),
+    Number(f64),
+    /// A number kept as its source text, see [`ParseOptions::raw_numbers`](crate::ParseOptions::raw_numbers).
+    RawNumber(&'a str),
+    Boolean(bool),
+    Null,
+    Object(Vec<(Cow<'a, str>, BorrowedValue<'a>)>),
+    Array(Vec<BorrowedValue<'a>>),
+}
+
+impl<'a> BorrowedValue<'a> {
+    /// The value of a member of an object, the last one if the key repeats,
+    /// or `None` for a missing key or other types.
+    pub fn get(&self, key: &str) -> Option<&BorrowedValue<'a>> {
+        match self {
+            BorrowedValue::Object(members) => {
+                members.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+
+    /// Borrows the contents of a string, or returns `None` for other types.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            BorrowedValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Copies the value into a [`Value`]. A repeated key keeps its first
+    /// position and its last value, as [`try_parse`](crate::try_parse) does.
+    pub fn into_owned(self) -> Value {
+        match self {
+            BorrowedValue::String(s) => Value::String(s.into_owned()),
+            BorrowedValue::Number(n) => Value::Number(n),
+            BorrowedValue::RawNumber(s) => Value::RawNumber(s.to_string()),
+            BorrowedValue::Boolean(b) => Value::Boolean(b),
+            BorrowedValue::Null => Value::Null,
+            BorrowedValue::Object(members) => Value::Object(
+                members
+                    .into_iter()
+                    .map(|(k, v)| (k, v.into_owned()))
+                    .collect::<Map>(),
+            ),
+            BorrowedValue::Array(elements) => Value::Array(
+                elements
+                    .into_iter()
+                    .map(BorrowedValue::into_owned)
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<BorrowedValue<'_>> for Value {
+    fn from(value: BorrowedValue<'_>) -> Self {
+        value.into_owned()
+    }
+}
+
+impl<'a> Tree<'a> for BorrowedValue<'a> {
+    type Members = Vec<(Cow<'a, str>, BorrowedValue<'a>)>;
+
+    fn null() -> Self {
+        BorrowedValue::Null
+    }
+
+    fn boolean(b: bool) -> Self {
+        BorrowedValue::Boolean(b)
+    }
+
+    fn number(n: f64) -> Self {
+        BorrowedValue::Number(n)
+    }
+
+    fn raw_number(text: &'a str) -> Self {
+        BorrowedValue::RawNumber(text)
+    }
+
+    fn string(s: Cow<'a, str>) -> Self {
+        BorrowedValue::String(s)
+    }
+
+    fn array(elements: Vec<Self>) -> Self {
+        BorrowedValue::Array(elements)
+    }
+
+    fn object(members: Self::Members) -> Self {
+        BorrowedValue::Object(members)
+    }
+
+    fn contains_key(members: &Self::Members, key: &str) -> bool {
+        members.iter().any(|(k, _)| k == key)
+    }
+
+    fn insert(members: &mut Self::Members, key: Cow<'a, str>, value: Self, _: &mut Parser) {
+        members.push((key, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParseOptions, parse_borrowed, try_parse};
+
+    #[test]
+    fn borrows_strings_without_escapes() {
+        let input = r#"{"name": "rj", "quote\"d": "tab\t"}"#;
+        let BorrowedValue::Object(members) = parse_borrowed(input).unwrap() else {
+            panic!("expected an object");
+        };
+        assert!(matches!(&members[0].0, Cow::Borrowed("name")));
+        assert!(matches!(
+            &members[0].1,
+            BorrowedValue::String(Cow::Borrowed("rj"))
+        ));
+        assert!(matches!(&members[1].0, Cow::Owned(k) if k == "quote\"d"));
+        assert!(matches!(&members[1].1, BorrowedValue::String(Cow::Owned(s)) if s == "tab\t"));
+    }
+
+    #[test]
+    fn into_owned_matches_try_parse() {
+        let input = r#"{"a": [1, 2.5, "x\n"], "b": {"c": null, "d": true}, "a": false}"#;
+        let borrowed = parse_borrowed(input).unwrap();
+        assert_eq!(borrowed.get("a"), Some(&BorrowedValue::Boolean(false)));
+        assert_eq!(borrowed.into_owned(), try_parse(input).unwrap());
+    }
+
+    #[test]
+    fn keeps_repeated_keys() {
+        let value = parse_borrowed(r#"{"a": 1, "a": 2}"#).unwrap();
+        let BorrowedValue::Object(members) = &value else {
+            panic!("expected an object");
+        };
+        assert_eq!(members.len(), 2);
+        assert_eq!(value.get("a"), Some(&BorrowedValue::Number(2.0)));
+    }
+
+    #[test]
+    fn applies_parse_options() {
+        let mut parser = Parser::with_options(ParseOptions {
+            raw_numbers: true,
+            reject_duplicate_keys: true,
+            ..ParseOptions::default()
+        });
+        assert_eq!(
+            parser.parse_borrowed("[1.50]").unwrap(),
+            BorrowedValue::Array(vec![BorrowedValue::RawNumber("1.50")])
+        );
+        assert!(parser.parse_borrowed(r#"{"a": 1, "a": 2}"#).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{Error, Map, Value};
+
+/// Builds a [`Value::Object`], rejecting keys that were already inserted
+/// instead of silently overwriting them.
+#[derive(Debug, Default)]
+pub struct StrictObjectBuilder {
+    members: Map,
+}
+
+impl StrictObjectBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: Value) -> Result<&mut Self, Error> {
+        let key = key.into();
+        if self.members.contains_key(&key) {
+            return Err(Error::DuplicateKey(key));
+        }
+        self.members.insert(key, value);
+        Ok(self)
+    }
+
+    pub fn build(self) -> Value {
+        Value::Object(self.members)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_object() {
+        let mut builder = StrictObjectBuilder::new();
+        builder
+            .insert("a", Value::Number(1.0))
+            .unwrap()
+            .insert("b", Value::Null)
+            .unwrap();
+        assert_eq!(builder.build(), Value::from(r#"{"a": 1, "b": null}"#));
+    }
+
+    #[test]
+    fn duplicate_key_errors() {
+        let mut builder = StrictObjectBuilder::new();
+        builder.insert("a", Value::Number(1.0)).unwrap();
+        let err = builder.insert("a", Value::Number(2.0)).unwrap_err();
+        assert_eq!(err, Error::DuplicateKey("a".to_string()));
+        assert_eq!(builder.build()["a"], Value::Number(1.0));
+    }
+}
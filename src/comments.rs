@@ -0,0 +1,282 @@
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{
+    FormatOptions, ParseError, ParseOptions, Value, generate::write_quoted, parse,
+    pointer::escape_token,
+};
+
+/// A document together with the `//` comments written before its object
+/// members, so that config files can be edited without losing them.
+///
+/// `comments` maps the JSON Pointer of a member (or `""` for the root value)
+/// to the comment lines above it, without the `//`. This is deliberately
+/// limited: only comments on their own lines directly before a member or the
+/// root are kept. Comments inside arrays, after a value on the same line or
+/// before a closing bracket are dropped. Members are written back in source
+/// order, each with its comments.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Commented {
+    pub value: Value,
+    pub comments: HashMap<String, Vec<String>>,
+}
+
+pub(crate) fn parse_commented(input: &str) -> Result<Commented, ParseError> {
+    let opts = ParseOptions {
+        allow_line_comments: true,
+        ..Default::default()
+    };
+    let value = parse::try_parse_with(input, &opts)?;
+    Ok(Commented {
+        value,
+        comments: collect_comments(input),
+    })
+}
+
+/// An open container while scanning, with the pointer to it.
+enum Frame {
+    /// `key` is the member being read, or `None` when a key comes next.
+    Object {
+        pointer: String,
+        key: Option<String>,
+    },
+    Array {
+        pointer: String,
+        index: usize,
+    },
+}
+
+/// Pointer to the value that starts next, given the open containers.
+fn next_pointer(stack: &[Frame]) -> String {
+    match stack.last() {
+        None => String::new(),
+        Some(Frame::Object { pointer, key }) => {
+            format!("{pointer}/{}", escape_token(key.as_deref().unwrap_or("")))
+        }
+        Some(Frame::Array { pointer, index }) => format!("{pointer}/{index}"),
+    }
+}
+
+/// Finds the comments preceding members in `input`, which must already be
+/// known to parse with line comments allowed.
+fn collect_comments(input: &str) -> HashMap<String, Vec<String>> {
+    let input = input.strip_prefix('\u{FEFF}').unwrap_or(input);
+    let bytes = input.as_bytes();
+    let mut comments = HashMap::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut pending: Vec<String> = Vec::new();
+    // Whether only whitespace precedes the current position on its line.
+    let mut own_line = true;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        match bytes[i] {
+            b'\n' => {
+                own_line = true;
+                i += 1;
+                continue;
+            }
+            b' ' | b'\t' | b'\r' => {
+                i += 1;
+                continue;
+            }
+            b'/' => {
+                let end = input[i..].find('\n').map_or(input.len(), |n| i + n);
+                if own_line {
+                    pending.push(input[i + 2..end].trim().to_string());
+                }
+                i = end;
+                continue;
+            }
+            _ if stack.is_empty() && !pending.is_empty() => {
+                // The first token at the top level starts the root value.
+                comments.insert(String::new(), std::mem::take(&mut pending));
+                continue;
+            }
+            b'{' | b'[' => {
+                let pointer = next_pointer(&stack);
+                stack.push(match bytes[i] {
+                    b'{' => Frame::Object { pointer, key: None },
+                    _ => Frame::Array { pointer, index: 0 },
+                });
+                i += 1;
+            }
+            b'}' | b']' => {
+                stack.pop();
+                i += 1;
+            }
+            b',' => {
+                match stack.last_mut() {
+                    Some(Frame::Object { key, .. }) => *key = None,
+                    Some(Frame::Array { index, .. }) => *index += 1,
+                    None => {}
+                }
+                i += 1;
+            }
+            b':' => i += 1,
+            b'"' => {
+                i += 1;
+                while bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+                if let Some(Frame::Object {
+                    key: key @ None, ..
+                }) = stack.last_mut()
+                {
+                    let text = parse::try_parse(&input[start..i]).ok();
+                    *key = Some(text.and_then(Value::into_string).unwrap_or_default());
+                    if !pending.is_empty() {
+                        comments.insert(next_pointer(&stack), std::mem::take(&mut pending));
+                    }
+                }
+            }
+            _ => {
+                while i < bytes.len() && !b",]} \t\r\n/".contains(&bytes[i]) {
+                    i += 1;
+                }
+            }
+        }
+        pending.clear();
+        own_line = false;
+    }
+    comments
+}
+
+impl Commented {
+    /// Serializes the document as indented JSON with its comments restored.
+    pub fn to_pretty_string(&self) -> String {
+        self.to_pretty_string_with(&FormatOptions::default())
+    }
+
+    /// Like [`Commented::to_pretty_string`], with output controlled by `opts`.
+    pub fn to_pretty_string_with(&self, opts: &FormatOptions) -> String {
+        let mut buf = String::new();
+        // Writing into a String cannot fail.
+        let _ = self.write_comments(&mut buf, "", 0, opts);
+        let _ = self.write_pretty(&mut buf, &self.value, "", opts.step(), opts);
+        buf
+    }
+
+    fn write_comments<W: Write>(
+        &self,
+        w: &mut W,
+        pointer: &str,
+        indent: usize,
+        opts: &FormatOptions,
+    ) -> std::fmt::Result {
+        for line in self.comments.get(pointer).into_iter().flatten() {
+            opts.pad(w, indent)?;
+            match line.as_str() {
+                "" => w.write_str("//\n")?,
+                line => writeln!(w, "// {line}")?,
+            }
+        }
+        Ok(())
+    }
+
+    fn write_pretty<W: Write>(
+        &self,
+        w: &mut W,
+        value: &Value,
+        pointer: &str,
+        indent: usize,
+        opts: &FormatOptions,
+    ) -> std::fmt::Result {
+        let close = indent.saturating_sub(opts.step());
+        match value {
+            Value::Object(obj) if !obj.is_empty() => {
+                w.write_str("{\n")?;
+                for (i, (k, v)) in obj.iter().enumerate() {
+                    let member = format!("{pointer}/{}", escape_token(k));
+                    self.write_comments(w, &member, indent, opts)?;
+                    opts.pad(w, indent)?;
+                    write_quoted(w, k)?;
+                    w.write_str(": ")?;
+                    self.write_pretty(w, v, &member, indent + opts.step(), opts)?;
+                    w.write_str(if i + 1 < obj.len() { ",\n" } else { "\n" })?;
+                }
+                opts.pad(w, close)?;
+                w.write_char('}')
+            }
+            Value::Array(arr) if !arr.is_empty() => {
+                w.write_str("[\n")?;
+                for (i, v) in arr.iter().enumerate() {
+                    opts.pad(w, indent)?;
+                    let element = format!("{pointer}/{i}");
+                    self.write_pretty(w, v, &element, indent + opts.step(), opts)?;
+                    w.write_str(if i + 1 < arr.len() { ",\n" } else { "\n" })?;
+                }
+                opts.pad(w, close)?;
+                w.write_char(']')
+            }
+            _ => value.write_pretty_to(w, opts),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"// Service settings
+{
+  // Port to listen on
+  "port": 8080,
+  "tls": {
+    // Paths are relative to the config file
+    //
+    // and must exist.
+    "cert": "a/b.pem"
+  },
+  "hosts": [
+    // dropped: inside an array
+    "a" // dropped: not on its own line
+  ]
+  // dropped: before a closing bracket
+}"#;
+
+    #[test]
+    fn comments_are_attached_to_members() {
+        let doc = parse_commented(CONFIG).unwrap();
+        assert_eq!(
+            doc.value,
+            Value::from(r#"{"port": 8080, "tls": {"cert": "a/b.pem"}, "hosts": ["a"]}"#)
+        );
+        let lines = |pointer: &str| doc.comments.get(pointer).map(Vec::as_slice);
+        assert_eq!(lines(""), Some(&["Service settings".to_string()][..]));
+        assert_eq!(lines("/port"), Some(&["Port to listen on".to_string()][..]));
+        assert_eq!(
+            lines("/tls/cert").unwrap(),
+            [
+                "Paths are relative to the config file",
+                "",
+                "and must exist."
+            ]
+        );
+        assert_eq!(doc.comments.len(), 3);
+    }
+
+    #[test]
+    fn commented_config_survives_round_trip() {
+        let doc = parse_commented(CONFIG).unwrap();
+        let out = doc.to_pretty_string();
+        assert_eq!(parse_commented(&out), Ok(doc));
+    }
+
+    #[test]
+    fn pretty_output_places_comments() {
+        let doc = parse_commented("// header\n{\n// key\n\"a\": {\"b\": 1}}").unwrap();
+        assert_eq!(
+            doc.to_pretty_string(),
+            "// header\n{\n  // key\n  \"a\": {\n    \"b\": 1\n  }\n}"
+        );
+        let doc = parse_commented("// just a number\n1").unwrap();
+        assert_eq!(doc.to_pretty_string(), "// just a number\n1");
+    }
+
+    #[test]
+    fn invalid_input_is_an_error() {
+        assert!(parse_commented("{\"a\": 1 /* block */}").is_err());
+    }
+}
@@ -0,0 +1,77 @@
+//! Exact decimal numbers for money and other values that `f64` rounds.
+//!
+//! Parse with [`ParseOptions::raw_numbers`](crate::ParseOptions::raw_numbers)
+//! so that numbers keep their source text, read them with
+//! [`Value::as_decimal`], and store results back with `Value::from`.
+
+use rust_decimal::Decimal;
+
+use crate::Value;
+
+impl Value {
+    /// The exact decimal value of a number, or `None` for other types and
+    /// for numbers outside the range of `Decimal`.
+    ///
+    /// A [`Value::Number`] converts from the shortest text that reads back as
+    /// the same `f64`, so `0.1` gives exactly `0.1`.
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        let text = match self {
+            Value::Number(n) if n.is_finite() => n.to_string(),
+            Value::RawNumber(s) => s.clone(),
+            _ => return None,
+        };
+        if text.contains(['e', 'E']) {
+            Decimal::from_scientific(&text).ok()
+        } else {
+            Decimal::from_str_exact(&text).ok()
+        }
+    }
+}
+
+/// Stores the decimal as a [`Value::RawNumber`], so it is serialized with
+/// exactly its digits.
+impl From<Decimal> for Value {
+    fn from(value: Decimal) -> Self {
+        Value::RawNumber(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseOptions;
+
+    #[test]
+    fn sum_is_exact() {
+        let opts = ParseOptions {
+            raw_numbers: true,
+            ..Default::default()
+        };
+        let prices = crate::try_parse_with(r#"{"a": 0.1, "b": 0.2}"#, &opts).unwrap();
+        let total = prices["a"].as_decimal().unwrap() + prices["b"].as_decimal().unwrap();
+        assert_eq!(Value::from(total).to_string(), "0.3");
+
+        // The same sum in `f64` is not exact.
+        let lossy = crate::parse(r#"{"a": 0.1, "b": 0.2}"#);
+        let sum: f64 = f64::try_from(lossy["a"].clone()).unwrap() + 0.2;
+        assert_eq!(Value::Number(sum).to_string(), "0.30000000000000004");
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(
+            Value::RawNumber("19.990".into())
+                .as_decimal()
+                .unwrap()
+                .to_string(),
+            "19.990"
+        );
+        assert_eq!(
+            Value::RawNumber("-1.5E2".into()).as_decimal(),
+            Some(Decimal::new(-150, 0))
+        );
+        assert_eq!(Value::Number(0.1).as_decimal(), Some(Decimal::new(1, 1)));
+        assert_eq!(Value::RawNumber("1e400".into()).as_decimal(), None);
+        assert_eq!(Value::Null.as_decimal(), None);
+    }
+}
@@ -0,0 +1,170 @@
+/// Error returned when the input is not a valid JSON text.
+///
+/// `offset` is a byte offset into the input; `line` and `column` are 1-based
+/// and count characters, not bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    message: String,
+    offset: usize,
+    line: usize,
+    column: usize,
+    pub(crate) kind: ErrorKind,
+    pub(crate) path: Vec<PathSegment>,
+}
+
+/// What went wrong in a [`ParseError`], for callers that handle some errors
+/// differently, e.g. treating an empty file as an empty document. The
+/// [message](ParseError::message) describes the error for people.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A character that does not fit the syntax at this point, e.g. a
+    /// missing `:` after an object key.
+    UnexpectedToken { found: char },
+    /// The input ended before the document was complete, e.g. because it is
+    /// empty, only whitespace, or stops inside a string.
+    UnexpectedEof,
+    /// A malformed number, such as `01` or `1.`, or one out of range.
+    InvalidNumber,
+    /// A backslash followed by a character that cannot be escaped.
+    InvalidEscape { escape: char },
+    /// A malformed `\u` escape, an unpaired surrogate or invalid UTF-8.
+    InvalidUnicode,
+    /// Arrays and objects nested deeper than
+    /// [`ParseOptions::max_depth`](crate::ParseOptions::max_depth).
+    DepthExceeded { limit: usize },
+    /// A key repeated in an object, with
+    /// [`ParseOptions::reject_duplicate_keys`](crate::ParseOptions::reject_duplicate_keys).
+    DuplicateKey { key: String },
+    /// More input after a complete document.
+    TrailingContent,
+}
+
+/// One step of the path to the value where a [`ParseError`] occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Writes the segment as a JSON Pointer token, including its leading `/`.
+impl std::fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathSegment::Key(key) => write!(f, "/{}", crate::pointer::escape_token(key)),
+            PathSegment::Index(i) => write!(f, "/{i}"),
+        }
+    }
+}
+
+impl ParseError {
+    pub(crate) fn new(
+        input: &str,
+        offset: usize,
+        kind: ErrorKind,
+        message: impl Into<String>,
+    ) -> Self {
+        let (line, column) = position(input, offset, 1);
+        ParseError {
+            message: message.into(),
+            offset,
+            line,
+            column,
+            kind,
+            path: Vec::new(),
+        }
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// Recomputes the column of an error in `input` with tabs advancing to
+    /// the next multiple of `tab_width` columns, as in most editors.
+    pub(crate) fn expand_tabs(&mut self, input: &str, tab_width: usize) {
+        self.column = position(input, self.offset, tab_width).1;
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Object keys and array indices leading from the root to the value that
+    /// was being parsed, empty for errors at the top level.
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// [`path`](Self::path) as a JSON Pointer, e.g. `/Image/IDs/3`.
+    pub fn pointer(&self) -> String {
+        self.path.iter().map(PathSegment::to_string).collect()
+    }
+}
+
+/// The 1-based line and column of `offset` in `input`, counting characters
+/// and moving to the next tab stop at every tab.
+fn position(input: &str, offset: usize, tab_width: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut column = 1;
+    for c in input[..offset].chars() {
+        match c {
+            '\n' => {
+                line += 1;
+                column = 1;
+            }
+            '\t' if tab_width > 1 => column += tab_width - (column - 1) % tab_width,
+            _ => column += 1,
+        }
+    }
+    (line, column)
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Error returned by operations on a [`Value`](crate::Value) that does not
+/// have the expected shape.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    MissingKey(String),
+    DuplicateKey(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::TypeMismatch { expected, found } => {
+                write!(f, "expected {expected}, found {found}")
+            }
+            Error::MissingKey(key) => write!(f, "missing key \"{key}\""),
+            Error::DuplicateKey(key) => write!(f, "duplicate key \"{key}\""),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
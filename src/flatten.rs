@@ -0,0 +1,292 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{Error, Map, Value, pointer::array_index};
+
+impl Value {
+    /// Flattens the document into `(path, value)` pairs for every scalar and
+    /// empty container, with path segments joined by `.` and array elements
+    /// addressed by their index, e.g. `("Image.IDs.0", Number(116.0))`.
+    ///
+    /// Object members are listed in document order. A scalar root yields a single
+    /// pair with an empty path.
+    pub fn flatten(&self) -> Vec<(String, Value)> {
+        let mut pairs = Vec::new();
+        flatten_into(self, String::new(), &mut pairs);
+        pairs
+    }
+
+    /// Rebuilds a document from the `(path, value)` pairs of
+    /// [`Value::flatten`]. Segments that are array indices create arrays;
+    /// any other segment creates an object member. An empty path sets the
+    /// root, and no pairs at all give an empty object.
+    ///
+    /// Empty keys, keys containing `.` and keys that look like array indices
+    /// do not survive a round trip. Using a path both as an object and as an
+    /// array is a [`Error::TypeMismatch`], setting one twice (or below a
+    /// value) is an [`Error::DuplicateKey`], and skipping an array index is
+    /// an [`Error::MissingKey`].
+    pub fn unflatten(pairs: &[(String, Value)]) -> Result<Value, Error> {
+        let mut root = Node::Empty;
+        for (path, value) in pairs {
+            let segments: Vec<&str> = match path.as_str() {
+                "" => Vec::new(),
+                _ => path.split('.').collect(),
+            };
+            let mut node = &mut root;
+            for (depth, segment) in segments.iter().enumerate() {
+                let index = array_index(segment);
+                if let Node::Empty = node {
+                    *node = match index {
+                        Some(_) => Node::Array(BTreeMap::new()),
+                        None => Node::Object {
+                            members: Vec::new(),
+                            index: HashMap::new(),
+                        },
+                    };
+                }
+                node = match (node, index) {
+                    (Node::Object { members, index }, None) => {
+                        let i = *index.entry(segment.to_string()).or_insert_with(|| {
+                            members.push((segment.to_string(), Node::Empty));
+                            members.len() - 1
+                        });
+                        &mut members[i].1
+                    }
+                    (Node::Array(elements), Some(i)) => elements.entry(i).or_insert(Node::Empty),
+                    (Node::Object { .. }, Some(_)) => {
+                        return Err(Error::TypeMismatch {
+                            expected: "array",
+                            found: "object",
+                        });
+                    }
+                    (Node::Array(_), None) => {
+                        return Err(Error::TypeMismatch {
+                            expected: "object",
+                            found: "array",
+                        });
+                    }
+                    (Node::Leaf(_) | Node::Empty, _) => {
+                        return Err(Error::DuplicateKey(segments[..depth].join(".")));
+                    }
+                };
+            }
+            if !matches!(node, Node::Empty) {
+                return Err(Error::DuplicateKey(path.clone()));
+            }
+            *node = Node::Leaf(value.clone());
+        }
+        match root {
+            Node::Empty => Ok(Value::Object(Map::new())),
+            root => root.build(""),
+        }
+    }
+}
+
+/// A partially rebuilt document, where array elements may still arrive in
+/// any order. Object members keep the order their first path arrived in.
+enum Node {
+    Empty,
+    Leaf(Value),
+    Object {
+        members: Vec<(String, Node)>,
+        index: HashMap<String, usize>,
+    },
+    Array(BTreeMap<usize, Node>),
+}
+
+impl Node {
+    fn build(self, path: &str) -> Result<Value, Error> {
+        let child = |segment: &str| match path {
+            "" => segment.to_string(),
+            _ => format!("{path}.{segment}"),
+        };
+        match self {
+            Node::Empty => Ok(Value::Null),
+            Node::Leaf(v) => Ok(v),
+            Node::Object { members, .. } => members
+                .into_iter()
+                .map(|(k, n)| n.build(&child(&k)).map(|v| (k, v)))
+                .collect::<Result<_, _>>()
+                .map(Value::Object),
+            Node::Array(elements) => {
+                let mut arr = Vec::with_capacity(elements.len());
+                for (i, n) in elements {
+                    if i != arr.len() {
+                        return Err(Error::MissingKey(child(&arr.len().to_string())));
+                    }
+                    arr.push(n.build(&child(&i.to_string()))?);
+                }
+                Ok(Value::Array(arr))
+            }
+        }
+    }
+}
+
+fn flatten_into(value: &Value, path: String, pairs: &mut Vec<(String, Value)>) {
+    let child = |segment: &str| match path.as_str() {
+        "" => segment.to_string(),
+        _ => format!("{path}.{segment}"),
+    };
+    match value {
+        Value::Object(obj) if !obj.is_empty() => {
+            for (key, v) in obj {
+                flatten_into(v, child(key), pairs);
+            }
+        }
+        Value::Array(arr) if !arr.is_empty() => {
+            for (i, v) in arr.iter().enumerate() {
+                flatten_into(v, child(&i.to_string()), pairs);
+            }
+        }
+        _ => pairs.push((path, value.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten_rfc_example() {
+        let v = Value::from(
+            r#"{
+                "Image": {
+                    "Width":  800,
+                    "Height": 600,
+                    "Title":  "View from 15th Floor",
+                    "Thumbnail": {
+                        "Url":    "http://www.example.com/image/481989943",
+                        "Height": 125,
+                        "Width":  100
+                    },
+                    "Animated" : false,
+                    "IDs": [116, 943, 234, 38793]
+                }
+            }"#,
+        );
+        let flat: Vec<(String, String)> = v
+            .flatten()
+            .into_iter()
+            .map(|(k, v)| (k, v.to_string()))
+            .collect();
+        assert_eq!(
+            flat,
+            [
+                ("Image.Width", "800"),
+                ("Image.Height", "600"),
+                ("Image.Title", "\"View from 15th Floor\""),
+                (
+                    "Image.Thumbnail.Url",
+                    "\"http://www.example.com/image/481989943\""
+                ),
+                ("Image.Thumbnail.Height", "125"),
+                ("Image.Thumbnail.Width", "100"),
+                ("Image.Animated", "false"),
+                ("Image.IDs.0", "116"),
+                ("Image.IDs.1", "943"),
+                ("Image.IDs.2", "234"),
+                ("Image.IDs.3", "38793"),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, Value)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), Value::from(*v)))
+            .collect()
+    }
+
+    #[test]
+    fn unflatten_mixed_paths() {
+        let v = Value::unflatten(&pairs(&[
+            ("a.list.1.name", r#""second""#),
+            ("a.list.0", "1"),
+            ("a.ok", "true"),
+            ("b", "[]"),
+            ("c.01", "null"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            v,
+            Value::from(
+                r#"{"a": {"list": [1, {"name": "second"}], "ok": true}, "b": [], "c": {"01": null}}"#
+            )
+        );
+        assert_eq!(
+            Value::unflatten(&pairs(&[("", "2")])),
+            Ok(Value::Number(2.0))
+        );
+        assert_eq!(Value::unflatten(&[]), Ok(Value::from("{}")));
+    }
+
+    #[test]
+    fn unflatten_conflicts() {
+        assert_eq!(
+            Value::unflatten(&pairs(&[("a.0", "1"), ("a.b", "2")])),
+            Err(Error::TypeMismatch {
+                expected: "object",
+                found: "array"
+            })
+        );
+        assert_eq!(
+            Value::unflatten(&pairs(&[("a.b", "1"), ("a.0", "2")])),
+            Err(Error::TypeMismatch {
+                expected: "array",
+                found: "object"
+            })
+        );
+        assert_eq!(
+            Value::unflatten(&pairs(&[("a.b", "1"), ("a.b", "2")])),
+            Err(Error::DuplicateKey("a.b".to_string()))
+        );
+        assert_eq!(
+            Value::unflatten(&pairs(&[("a", "1"), ("a.b.c", "2")])),
+            Err(Error::DuplicateKey("a".to_string()))
+        );
+        assert_eq!(
+            Value::unflatten(&pairs(&[("x.0", "1"), ("x.2", "2")])),
+            Err(Error::MissingKey("x.1".to_string()))
+        );
+    }
+
+    #[test]
+    fn unflatten_reverses_flatten() {
+        let docs = [
+            r#"{"Image": {"IDs": [116, 943, 234, 38793, 1, 2, 3, 4, 5, 6, 7, 8]}}"#,
+            r#"[{"a": [[], {}]}, "x", null, {"b": {"c": [false]}}]"#,
+            r#"{"a": {"k": 1, "l": "m"}, "empty": {}}"#,
+        ];
+        for doc in docs {
+            let v = Value::from(doc);
+            assert_eq!(Value::unflatten(&v.flatten()), Ok(v), "{doc}");
+        }
+    }
+
+    #[test]
+    fn round_trip_keeps_member_order() {
+        let v = Value::from(r#"{"z": {"y": 1, "b": [2, {"q": 3, "c": 4}]}, "a": null, "m": {}}"#);
+        let keys: Vec<String> = v.flatten().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, ["z.y", "z.b.0", "z.b.1.q", "z.b.1.c", "a", "m"]);
+        let rebuilt = Value::unflatten(&v.flatten()).unwrap();
+        assert_eq!(rebuilt.to_compact_string(), v.to_compact_string());
+    }
+
+    #[test]
+    fn flatten_keeps_empty_containers_and_scalar_roots() {
+        let v = Value::from(r#"{"a": {}, "b": [[]], "c": null}"#);
+        assert_eq!(
+            v.flatten(),
+            vec![
+                ("a".to_string(), Value::from("{}")),
+                ("b.0".to_string(), Value::from("[]")),
+                ("c".to_string(), Value::Null),
+            ]
+        );
+        assert_eq!(
+            Value::Boolean(true).flatten(),
+            vec![(String::new(), Value::Boolean(true))]
+        );
+    }
+}
@@ -1,91 +1,478 @@
-use crate::Value;
+use std::fmt::Write;
+
+use crate::{Value, value::decimal};
 
 impl std::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Value::String(x) => write!(f, "\"{x}\""),
-            Value::Number(x) => write!(f, "{x}"),
-            Value::Boolean(x) => write!(f, "{x}"),
-            Value::Null => write!(f, "null"),
-            Value::Object(obj) => {
-                let mut buf = String::new();
-                buf.push('{');
-                let mut members: Vec<String> = Vec::new();
-                for (k, v) in obj.iter() {
-                    members.push(format!("{}:{}", &Value::String(k.to_string()), v));
+        self.write_to(f)
+    }
+}
+
+/// Writes `s` as a quoted JSON string, escaping the characters that may not
+/// appear raw inside one.
+pub(crate) fn write_quoted<W: Write>(w: &mut W, s: &str) -> std::fmt::Result {
+    write_quoted_with(w, s, &FormatOptions::default())
+}
+
+/// Like [`write_quoted`], also escaping what the escaping options of `opts`
+/// ask for.
+pub(crate) fn write_quoted_with<W: Write>(
+    w: &mut W,
+    s: &str,
+    opts: &FormatOptions,
+) -> std::fmt::Result {
+    w.write_char('"')?;
+    for c in s.chars() {
+        write_escaped_char(w, c, opts)?;
+    }
+    w.write_char('"')
+}
+
+/// Writes `c` as it appears inside a JSON string literal.
+pub(crate) fn write_escaped_char<W: Write>(
+    w: &mut W,
+    c: char,
+    opts: &FormatOptions,
+) -> std::fmt::Result {
+    match c {
+        '"' => w.write_str("\\\""),
+        '/' if opts.escape_forward_slash => w.write_str("\\/"),
+        '\\' => w.write_str("\\\\"),
+        '\x08' => w.write_str("\\b"),
+        '\x0C' => w.write_str("\\f"),
+        '\n' => w.write_str("\\n"),
+        '\r' => w.write_str("\\r"),
+        '\t' => w.write_str("\\t"),
+        c if c < '\x20' => write!(w, "\\u{:04x}", c as u32),
+        c if opts.ensure_ascii && !c.is_ascii() => {
+            // Characters beyond the BMP take two escapes, as a UTF-16
+            // surrogate pair.
+            let mut units = [0; 2];
+            for unit in c.encode_utf16(&mut units) {
+                write!(w, "\\u{:04x}", unit)?;
+            }
+            Ok(())
+        }
+        c => w.write_char(c),
+    }
+}
+
+/// Forwards `fmt::Write` output to an `io::Write`, keeping the I/O error
+/// that `fmt::Error` cannot carry.
+struct IoWriter<W> {
+    inner: W,
+    error: Option<std::io::Error>,
+}
+
+impl<W: std::io::Write> Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            std::fmt::Error
+        })
+    }
+}
+
+impl<W: std::io::Write> IoWriter<W> {
+    fn run(inner: W, f: impl FnOnce(&mut Self) -> std::fmt::Result) -> std::io::Result<()> {
+        let mut w = IoWriter { inner, error: None };
+        match f(&mut w) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(w
+                .error
+                .unwrap_or_else(|| std::io::Error::other("formatter error"))),
+        }
+    }
+}
+
+impl Value {
+    /// Serializes the value as single-line JSON without insignificant whitespace.
+    pub fn to_compact_string(&self) -> String {
+        let mut buf = String::with_capacity(self.serialized_len_estimate());
+        // Writing into a String cannot fail.
+        let _ = self.write_to(&mut buf);
+        buf
+    }
+
+    /// Roughly how many bytes [`Value::to_compact_string`] produces, used to
+    /// size output buffers up front. Escapes in strings are not counted and
+    /// numbers are guessed, so treat this as a capacity hint, not a bound.
+    pub fn serialized_len_estimate(&self) -> usize {
+        estimate_len(self, None, 0)
+    }
+
+    /// Serializes the value as JSON indented by two spaces per level.
+    pub fn to_pretty_string(&self) -> String {
+        self.to_pretty_string_with(&FormatOptions::default())
+    }
+
+    /// Serializes the value as indented JSON laid out according to `opts`.
+    pub fn to_pretty_string_with(&self, opts: &FormatOptions) -> String {
+        format_with(self, opts.step(), opts)
+    }
+
+    /// Serializes the value as canonical JSON for hashing and signing.
+    ///
+    /// Like [`Value::to_compact_string`], but object members are sorted by
+    /// the code points of their keys and numbers are written by value, so
+    /// values that compare equal always produce identical bytes: `-0` is
+    /// written as `0`, and a [`Value::RawNumber`] such as `1.50` or `15e-1`
+    /// like the `f64` `1.5` would be.
+    pub fn to_canonical_string(&self) -> String {
+        let mut buf = String::with_capacity(self.serialized_len_estimate());
+        // Writing into a String cannot fail.
+        let _ = write_canonical(&mut buf, self);
+        buf
+    }
+
+    /// Serializes the value as compact JSON, escaping strings as `opts`
+    /// asks. Options that only concern layout, such as `indent`, are ignored.
+    pub fn to_compact_string_with(&self, opts: &FormatOptions) -> String {
+        let mut buf = String::with_capacity(self.serialized_len_estimate());
+        // Writing into a String cannot fail.
+        let _ = write_compact(&mut buf, self, opts);
+        buf
+    }
+
+    /// Writes the value as compact JSON into `w` without building an
+    /// intermediate `String`.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> std::fmt::Result {
+        write_compact(w, self, &FormatOptions::default())
+    }
+
+    /// Writes the value as indented JSON into `w`.
+    pub fn write_pretty_to<W: Write>(&self, w: &mut W, opts: &FormatOptions) -> std::fmt::Result {
+        write_pretty(w, self, opts.step(), opts)
+    }
+
+    /// Writes the value as compact JSON into an `io::Write` such as a file.
+    pub fn to_writer<W: std::io::Write>(&self, w: W) -> std::io::Result<()> {
+        IoWriter::run(w, |w| self.write_to(w))
+    }
+
+    /// Writes the value as indented JSON into an `io::Write` such as a file.
+    pub fn to_writer_pretty<W: std::io::Write>(
+        &self,
+        w: W,
+        opts: &FormatOptions,
+    ) -> std::io::Result<()> {
+        IoWriter::run(w, |w| self.write_pretty_to(w, opts))
+    }
+}
+
+fn write_compact<W: Write>(w: &mut W, value: &Value, opts: &FormatOptions) -> std::fmt::Result {
+    match value {
+        Value::String(x) => write_quoted_with(w, x, opts),
+        Value::Number(x) => write_number(w, *x),
+        Value::RawNumber(x) => w.write_str(x),
+        Value::Boolean(x) => write!(w, "{x}"),
+        Value::Null => w.write_str("null"),
+        Value::Object(obj) => {
+            w.write_char('{')?;
+            for (i, (k, v)) in obj.iter().enumerate() {
+                if i > 0 {
+                    w.write_char(',')?;
+                }
+                write_quoted_with(w, k, opts)?;
+                w.write_char(':')?;
+                write_compact(w, v, opts)?;
+            }
+            w.write_char('}')
+        }
+        Value::Array(arr) => {
+            w.write_char('[')?;
+            for (i, v) in arr.iter().enumerate() {
+                if i > 0 {
+                    w.write_char(',')?;
+                }
+                write_compact(w, v, opts)?;
+            }
+            w.write_char(']')
+        }
+    }
+}
+
+/// Estimates the output length of `value`, compact when `step` is `None`,
+/// otherwise pretty with `step` indent characters per level starting at
+/// `indent`.
+fn estimate_len(value: &Value, step: Option<usize>, indent: usize) -> usize {
+    // Room for a newline and indentation before each member or element.
+    let line = |indent: usize| step.map_or(0, |_| 1 + indent);
+    let inner = indent + step.unwrap_or(0);
+    match value {
+        Value::String(s) => s.len() + 2,
+        Value::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => {
+            // Digits of an integer, plus its sign.
+            n.abs().max(1.0).log10() as usize + 1 + usize::from(*n < 0.0)
+        }
+        Value::Number(_) => 20,
+        Value::RawNumber(s) => s.len(),
+        Value::Boolean(true) | Value::Null => 4,
+        Value::Boolean(false) => 5,
+        Value::Object(obj) => {
+            let members: usize = obj
+                .iter()
+                .map(|(k, v)| line(indent) + k.len() + 4 + estimate_len(v, step, inner))
+                .sum();
+            2 + members + line(indent)
+        }
+        Value::Array(arr) => {
+            let elements: usize = arr
+                .iter()
+                .map(|v| line(indent) + 1 + estimate_len(v, step, inner))
+                .sum();
+            2 + elements + line(indent)
+        }
+    }
+}
+
+fn write_canonical<W: Write>(w: &mut W, value: &Value) -> std::fmt::Result {
+    // Equality compares numbers by these decimal digits, see `PartialEq`.
+    let digits = match value {
+        Value::Number(n) if n.is_finite() => decimal(&format!("{n:e}")),
+        Value::RawNumber(s) => decimal(s),
+        _ => None,
+    };
+    if let Some((negative, digits, exp)) = digits {
+        return write_decimal(w, negative, &digits, exp);
+    }
+    match value {
+        Value::Object(obj) => {
+            let mut members: Vec<_> = obj.iter().collect();
+            members.sort_unstable_by(|a, b| a.0.cmp(b.0));
+            w.write_char('{')?;
+            for (i, (k, v)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    w.write_char(',')?;
                 }
-                buf.push_str(&members.join(","));
-                buf.push('}');
-                write!(f, "{buf}")
+                write_quoted(w, k)?;
+                w.write_char(':')?;
+                write_canonical(w, v)?;
             }
-            Value::Array(arr) => {
-                let mut buf = String::new();
-                buf.push('[');
-                let mut elements: Vec<String> = Vec::new();
-                for v in arr {
-                    elements.push(v.to_string());
+            w.write_char('}')
+        }
+        Value::Array(arr) => {
+            w.write_char('[')?;
+            for (i, v) in arr.iter().enumerate() {
+                if i > 0 {
+                    w.write_char(',')?;
                 }
-                buf.push_str(&elements.join(","));
-                buf.push(']');
-                write!(f, "{buf}")
+                write_canonical(w, v)?;
             }
+            w.write_char(']')
         }
+        scalar => scalar.write_to(w),
     }
 }
 
+/// Writes `digits * 10^exp` the way [`write_number`] writes an `f64` with
+/// those shortest digits: positionally for exponents from -6 to 20, in
+/// scientific notation otherwise. Zero has no digits.
+fn write_decimal<W: Write>(w: &mut W, negative: bool, digits: &str, exp: i64) -> std::fmt::Result {
+    if digits.is_empty() {
+        return w.write_char('0');
+    }
+    if negative {
+        w.write_char('-')?;
+    }
+    let len = digits.len() as i64;
+    let scientific = exp + len - 1;
+    if !(-6..21).contains(&scientific) {
+        let (first, rest) = digits.split_at(1);
+        w.write_str(first)?;
+        if !rest.is_empty() {
+            write!(w, ".{rest}")?;
+        }
+        return write!(w, "e{scientific}");
+    }
+    if exp >= 0 {
+        return write!(w, "{digits}{}", "0".repeat(exp as usize));
+    }
+    let point = len + exp;
+    if point > 0 {
+        let (int, frac) = digits.split_at(point as usize);
+        write!(w, "{int}.{frac}")
+    } else {
+        write!(w, "0.{}{digits}", "0".repeat(-point as usize))
+    }
+}
+
+/// Options for pretty output, and for how strings are escaped in any output.
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    /// Number of spaces per nesting level. Ignored when `use_tabs` is set.
+    pub indent: usize,
+    /// Indent with one tab per nesting level instead of spaces.
+    pub use_tabs: bool,
+    /// Separate thousands in numbers with `,`, e.g. `1234567` as `1,234,567`.
+    ///
+    /// The output is no longer valid JSON, so only enable this for display.
+    pub group_digits: bool,
+    /// Highlight keys, strings, numbers and literals with ANSI escape codes,
+    /// for printing to a terminal.
+    pub color: bool,
+    /// Write `/` in strings as `\/`, so that output embedded in an HTML
+    /// `<script>` element cannot close it with `</script>`.
+    pub escape_forward_slash: bool,
+    /// Write every non-ASCII character in strings as a `\uXXXX` escape, for
+    /// consumers that cannot handle raw UTF-8.
+    pub ensure_ascii: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent: 2,
+            use_tabs: false,
+            group_digits: false,
+            color: false,
+            escape_forward_slash: false,
+            ensure_ascii: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Width of one nesting level, counted in indent characters.
+    pub(crate) fn step(&self) -> usize {
+        if self.use_tabs { 1 } else { self.indent }
+    }
+
+    pub(crate) fn pad<W: Write>(&self, w: &mut W, width: usize) -> std::fmt::Result {
+        let c = if self.use_tabs { '\t' } else { ' ' };
+        (0..width).try_for_each(|_| w.write_char(c))
+    }
+
+    /// Writes a token through `write`, wrapped in the ANSI color `code` when
+    /// `color` is set.
+    fn paint<W: Write>(
+        &self,
+        w: &mut W,
+        code: &str,
+        write: impl FnOnce(&mut W) -> std::fmt::Result,
+    ) -> std::fmt::Result {
+        if !self.color {
+            return write(w);
+        }
+        write!(w, "\x1b[{code}m")?;
+        write(w)?;
+        w.write_str("\x1b[0m")
+    }
+}
+
+const KEY_COLOR: &str = "1;34";
+const STRING_COLOR: &str = "32";
+const NUMBER_COLOR: &str = "36";
+const LITERAL_COLOR: &str = "35";
+
 pub(crate) fn format(value: &Value, indent: usize) -> String {
+    format_with(value, indent, &FormatOptions::default())
+}
+
+pub(crate) fn format_with(value: &Value, indent: usize, opts: &FormatOptions) -> String {
+    let mut buf = String::with_capacity(estimate_len(value, Some(opts.step()), indent));
+    // Writing into a String cannot fail.
+    let _ = write_pretty(&mut buf, value, indent, opts);
+    buf
+}
+
+fn write_pretty<W: Write>(
+    w: &mut W,
+    value: &Value,
+    indent: usize,
+    opts: &FormatOptions,
+) -> std::fmt::Result {
     match value {
-        Value::String(x) => format!("\"{x}\""),
-        Value::Number(x) => x.to_string(),
-        Value::Boolean(x) => x.to_string(),
-        Value::Null => "null".to_string(),
+        Value::String(s) => opts.paint(w, STRING_COLOR, |w| write_quoted_with(w, s, opts)),
+        Value::Number(x) => opts.paint(w, NUMBER_COLOR, |w| w.write_str(&format_number(*x, opts))),
+        Value::RawNumber(_) => opts.paint(w, NUMBER_COLOR, |w| value.write_to(w)),
+        Value::Boolean(_) | Value::Null => opts.paint(w, LITERAL_COLOR, |w| value.write_to(w)),
         Value::Object(obj) => {
             if obj.is_empty() {
-                return "{}".to_string();
+                return w.write_str("{}");
             }
-            let mut buf = String::new();
-            buf.push_str("{\n");
-            buf.push_str(&" ".repeat(indent));
+            w.write_str("{\n")?;
+            opts.pad(w, indent)?;
             for (i, (k, v)) in obj.iter().enumerate() {
-                buf.push_str(&format!("\"{k}\""));
-                buf.push_str(": ");
-                buf.push_str(&format(v, indent + 2));
+                opts.paint(w, KEY_COLOR, |w| write_quoted_with(w, k, opts))?;
+                w.write_str(": ")?;
+                write_pretty(w, v, indent + opts.step(), opts)?;
                 if i < obj.len() - 1 {
-                    buf.push_str(",\n");
-                    buf.push_str(&" ".repeat(indent));
+                    w.write_str(",\n")?;
+                    opts.pad(w, indent)?;
                 }
             }
-            buf.push('\n');
-            buf.push_str(&" ".repeat(indent - 2));
-            buf.push('}');
-            buf
+            w.write_char('\n')?;
+            opts.pad(w, indent.saturating_sub(opts.step()))?;
+            w.write_char('}')
         }
         Value::Array(arr) => {
             if arr.is_empty() {
-                return "[]".to_string();
+                return w.write_str("[]");
             }
-            let mut buf = String::new();
-            buf.push('[');
-            buf.push('\n');
-            buf.push_str(&" ".repeat(indent));
+            w.write_str("[\n")?;
+            opts.pad(w, indent)?;
             for (i, ele) in arr.iter().enumerate() {
-                buf.push_str(&format(ele, indent + 2));
+                write_pretty(w, ele, indent + opts.step(), opts)?;
                 if i < arr.len() - 1 {
-                    buf.push_str(",\n");
-                    buf.push_str(&" ".repeat(indent));
+                    w.write_str(",\n")?;
+                    opts.pad(w, indent)?;
                 }
             }
-            buf.push('\n');
-            buf.push_str(&" ".repeat(indent - 2));
-            buf.push(']');
-            buf
+            w.write_char('\n')?;
+            opts.pad(w, indent.saturating_sub(opts.step()))?;
+            w.write_char(']')
+        }
+    }
+}
+
+/// Writes the shortest decimal text that parses back to exactly `x`.
+///
+/// Like JavaScript's `Number.prototype.toString`, plain notation is used for
+/// decimal exponents from -7 to 20 exclusive (`0.000001`,
+/// `100000000000000000000`) and scientific notation otherwise (`1e-7`,
+/// `1e21`). JSON has no infinity or NaN, so those are written as `null`.
+fn write_number<W: Write>(w: &mut W, x: f64) -> std::fmt::Result {
+    if !x.is_finite() {
+        return w.write_str("null");
+    }
+    let scientific = format!("{x:e}");
+    let exponent: i32 = scientific
+        .rsplit_once('e')
+        .and_then(|(_, e)| e.parse().ok())
+        .unwrap_or(0);
+    if (-6..21).contains(&exponent) {
+        write!(w, "{x}")
+    } else {
+        w.write_str(&scientific)
+    }
+}
+
+fn format_number(x: f64, opts: &FormatOptions) -> String {
+    let mut s = String::new();
+    let _ = write_number(&mut s, x);
+    if !opts.group_digits || !x.is_finite() || s.contains('e') {
+        return s;
+    }
+    let (sign, rest) = match s.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", s.as_str()),
+    };
+    let (int, frac) = rest.split_at(rest.find('.').unwrap_or(rest.len()));
+    let mut grouped = String::new();
+    for (i, c) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            grouped.push(',');
         }
+        grouped.push(c);
     }
+    format!("{sign}{grouped}{frac}")
 }
 
 #[cfg(test)]
 mod generate_tests {
     use super::*;
+    use crate::Map;
 
     #[test]
     fn string() {
@@ -134,11 +521,220 @@ mod generate_tests {
     }
 
     #[test]
-    #[ignore = "order of keys is not guaranteed"]
+    fn object_key_is_escaped() {
+        let json = r#"{"a\"b":1}"#;
+        let value = Value::from(json);
+        let s = value.to_string();
+        assert_eq!(s, json);
+        assert_eq!(Value::from(s.as_str()), value);
+
+        let value = Value::from(r#"{"tab\tand\\slash": {}, "": []}"#);
+        assert_eq!(Value::from(value.to_string().as_str()), value);
+    }
+
+    #[test]
+    fn escape_forward_slash() {
+        let value = Value::from(r#"{"html": "<script>a/b</script>", "name": "café"}"#);
+        let opts = FormatOptions {
+            escape_forward_slash: true,
+            ..Default::default()
+        };
+        let compact = value.to_compact_string_with(&opts);
+        assert!(compact.contains(r#""<script>a\/b<\/script>""#), "{compact}");
+        // Non-ASCII characters are still written as they are.
+        assert!(compact.contains(r#""café""#), "{compact}");
+        assert!(!compact.contains("</"));
+        assert_eq!(Value::from(compact.as_str()), value);
+
+        let pretty = value.to_pretty_string_with(&opts);
+        assert!(pretty.contains(r#""<script>a\/b<\/script>""#), "{pretty}");
+        assert_eq!(Value::from(pretty.as_str()), value);
+
+        // Off by default.
+        assert!(value.to_compact_string().contains("</script>"));
+    }
+
+    #[test]
+    fn ensure_ascii() {
+        let opts = FormatOptions {
+            ensure_ascii: true,
+            ..Default::default()
+        };
+        let hiragana = Value::from(r#""あ""#);
+        assert_eq!(hiragana, Value::String("あ".into()));
+        assert_eq!(hiragana.to_compact_string_with(&opts), r#""\u3042""#);
+
+        let emoji = Value::from(r#""\ud83d\ude00""#);
+        assert_eq!(emoji, Value::String("😀".into()));
+        let escaped = emoji.to_compact_string_with(&opts);
+        assert_eq!(escaped, r#""\ud83d\ude00""#);
+        assert_eq!(Value::from(escaped.as_str()), emoji);
+
+        let doc = Value::from(r#"{"café": ["é", "plain", "\n"]}"#);
+        let pretty = doc.to_pretty_string_with(&opts);
+        assert!(pretty.is_ascii(), "{pretty}");
+        assert!(pretty.contains(r#""caf\u00e9""#), "{pretty}");
+        assert_eq!(Value::from(pretty.as_str()), doc);
+
+        // UTF-8 is written as is by default.
+        assert_eq!(hiragana.to_compact_string(), "\"あ\"");
+    }
+
+    #[test]
+    fn len_estimate_is_close() {
+        let value = Value::from(
+            r#"{"id": 123, "name": "rj", "tags": ["a", "b"], "ok": true, "none": null,
+                "nested": {"x": -4500, "y": [[], {}]}}"#,
+        );
+        let compact = value.to_compact_string();
+        let estimate = value.serialized_len_estimate();
+        assert!(
+            estimate >= compact.len() && estimate <= compact.len() * 5 / 4,
+            "{estimate} for {}",
+            compact.len()
+        );
+
+        let pretty = value.to_pretty_string();
+        let estimate = estimate_len(&value, Some(2), 2);
+        assert!(
+            estimate >= pretty.len() && estimate <= pretty.len() * 5 / 4,
+            "{estimate} for {}",
+            pretty.len()
+        );
+        assert_eq!(Value::Number(0.0).serialized_len_estimate(), 1);
+        assert_eq!(Value::Number(-10.0).serialized_len_estimate(), 3);
+    }
+
+    #[test]
+    fn integral_number_has_no_fraction() {
+        assert_eq!(Value::from("800").to_string(), "800");
+        assert_eq!(Value::from("800.0").to_string(), "800");
+        assert_eq!(Value::Number(800.0).to_string(), "800");
+        assert_eq!(Value::Number(400.0 * 2.0).to_string(), "800");
+        assert_eq!(Value::Number(-0.0).to_string(), "-0");
+        assert_eq!(format(&Value::Number(800.0), 2), "800");
+        assert_eq!(format(&"[800.0]".into(), 2), "[\n  800\n]");
+    }
+
+    #[test]
+    fn number_formatting_policy() {
+        let cases = [
+            (0.1, "0.1"),
+            (800.0, "800"),
+            (1e20, "100000000000000000000"),
+            (1e21, "1e21"),
+            (1e-6, "0.000001"),
+            (1e-7, "1e-7"),
+            (-1.5e-7, "-1.5e-7"),
+            (123456.789, "123456.789"),
+            (1.7976931348623157e308, "1.7976931348623157e308"),
+            (5e-324, "5e-324"),
+            (f64::INFINITY, "null"),
+            (f64::NAN, "null"),
+        ];
+        for (x, text) in cases {
+            assert_eq!(Value::Number(x).to_string(), text);
+            assert_eq!(format(&Value::Number(x), 2), text);
+            if x.is_finite() {
+                assert_eq!(crate::parse(text), Value::Number(x), "{text}");
+            }
+        }
+        let grouped = FormatOptions {
+            group_digits: true,
+            ..Default::default()
+        };
+        assert_eq!(Value::Number(1e21).to_pretty_string_with(&grouped), "1e21");
+    }
+
+    #[test]
+    fn write_to_writer() {
+        let value = Value::from(r#"[1, "a\nb", {"k": null}]"#);
+        let mut buf = String::new();
+        value.write_to(&mut buf).unwrap();
+        assert_eq!(buf, r#"[1,"a\nb",{"k":null}]"#);
+
+        let mut bytes: Vec<u8> = Vec::new();
+        value.to_writer(&mut bytes).unwrap();
+        assert_eq!(bytes, buf.as_bytes());
+
+        let mut bytes: Vec<u8> = Vec::new();
+        value
+            .to_writer_pretty(&mut bytes, &FormatOptions::default())
+            .unwrap();
+        assert_eq!(bytes, format(&value, 2).as_bytes());
+    }
+
+    #[test]
+    fn canonical_is_independent_of_key_order() {
+        let mut a = Map::new();
+        let mut b = Map::new();
+        let members = [
+            ("b", "1"),
+            ("a", r#"{"z": -0, "é": "\n", "y": 1e2}"#),
+            ("aa", "[]"),
+        ];
+        for (k, v) in members {
+            a.insert(k.to_string(), Value::from(v));
+        }
+        for (k, v) in members.iter().rev() {
+            b.insert(k.to_string(), Value::from(*v));
+        }
+        let (a, b) = (Value::Object(a), Value::Object(b));
+        assert_eq!(a.to_canonical_string(), b.to_canonical_string());
+        assert_eq!(
+            a.to_canonical_string(),
+            r#"{"a":{"y":100,"z":0,"é":"\n"},"aa":[],"b":1}"#
+        );
+    }
+
+    #[test]
+    fn canonical_numbers_by_value() {
+        let raw = |s: &str| Value::RawNumber(s.to_string());
+        for v in [raw("10"), raw("1e1"), raw("100E-1"), Value::Number(10.0)] {
+            assert_eq!(v.to_canonical_string(), "10");
+        }
+        for x in [
+            0.1, -1.5, 123.456, 1e-6, 1.5e-7, 1e20, 1e21, -2.5e300, 5e-324,
+        ] {
+            let expected = Value::Number(x).to_compact_string();
+            assert_eq!(Value::Number(x).to_canonical_string(), expected);
+            assert_eq!(raw(&format!("{x:e}")).to_canonical_string(), expected);
+            assert_eq!(raw(&x.to_string()).to_canonical_string(), expected);
+        }
+        assert_eq!(raw("-0.00").to_canonical_string(), "0");
+        // Digits beyond `f64` precision are kept.
+        assert_eq!(
+            raw("12345678901234567890.0").to_canonical_string(),
+            "12345678901234567890"
+        );
+        assert_eq!(
+            raw("1.0000000000000000001E+30").to_canonical_string(),
+            "1.0000000000000000001e30"
+        );
+        assert_eq!(raw("abc").to_canonical_string(), "abc");
+    }
+
+    #[test]
+    fn compact_drops_whitespace() {
+        let json = r#"{ "a" : [ 1 , true , null ] }"#;
+        let s = Value::from(json).to_compact_string();
+        assert_eq!(s, r#"{"a":[1,true,null]}"#);
+    }
+
+    #[test]
     fn object_with_members() {
         let json = r#"{"key":"value","key2":"value2"}"#;
         let s = Value::from(json).to_string();
         assert_eq!(s, json);
+        assert_eq!(Value::from(json).to_compact_string(), json);
+    }
+
+    #[test]
+    fn members_keep_input_order() {
+        let json = r#"{"z":1,"a":{"y":[],"b":null,"x":{}},"m":"s"}"#;
+        let value = Value::from(json);
+        assert_eq!(value.to_compact_string(), json);
+        assert_eq!(Value::from(format(&value, 2).as_str()).to_string(), json);
     }
 }
 
@@ -153,6 +749,17 @@ mod format_tests {
         assert_eq!(formatted, "{\n  \"key\": \"value\"\n}");
     }
 
+    #[test]
+    fn object_with_members() {
+        let json = "{\n  \"key\": \"value\",\n  \"key2\": \"value2\"\n}";
+        assert_eq!(format(&json.into(), 2), json);
+        let compact = r#"{"key":"value","key2":"value2"}"#;
+        assert_eq!(format(&compact.into(), 2), json);
+
+        let reversed = "{\n  \"key2\": \"value2\",\n  \"key\": \"value\"\n}";
+        assert_eq!(format(&reversed.into(), 2), reversed);
+    }
+
     #[test]
     fn test_nested_object() {
         let json = r#"{"key":{"key2":"value2"}}"#;
@@ -177,6 +784,13 @@ mod format_tests {
         assert_eq!(formatted, "{\n  \"key\": {}\n}");
     }
 
+    #[test]
+    fn test_object_with_empty_array() {
+        let json = r#"{"key": [], "other": {}}"#;
+        let formatted = format(&json.into(), 2);
+        assert_eq!(formatted, "{\n  \"key\": [],\n  \"other\": {}\n}");
+    }
+
     #[test]
     fn test_array() {
         let json = r#"[1,2,3]"#;
@@ -207,4 +821,123 @@ mod format_tests {
         let formatted = format(&json.into(), 2);
         assert_eq!(formatted, "[\n  [],\n  [\n    []\n  ]\n]");
     }
+
+    #[test]
+    fn test_to_pretty_string() {
+        let value = Value::from(r#"{"key":[1]}"#);
+        assert_eq!(value.to_pretty_string(), "{\n  \"key\": [\n    1\n  ]\n}");
+        let opts = FormatOptions {
+            indent: 4,
+            ..Default::default()
+        };
+        assert_eq!(
+            value.to_pretty_string_with(&opts),
+            "{\n    \"key\": [\n        1\n    ]\n}"
+        );
+    }
+
+    #[test]
+    fn test_zero_indent() {
+        let json = r#"{"key":[1,{}]}"#;
+        let formatted = format(&json.into(), 0);
+        assert_eq!(formatted, "{\n\"key\": [\n  1,\n  {}\n]\n}");
+        assert_eq!(format(&"[]".into(), 0), "[]");
+        assert_eq!(format(&"[[1]]".into(), 1), "[\n [\n   1\n ]\n]");
+    }
+
+    #[test]
+    fn test_group_digits() {
+        let opts = FormatOptions {
+            group_digits: true,
+            ..Default::default()
+        };
+        assert_eq!(format_with(&"1234567".into(), 2, &opts), "1,234,567");
+        assert_eq!(format_with(&"-1234.5".into(), 2, &opts), "-1,234.5");
+        assert_eq!(format_with(&"[999]".into(), 2, &opts), "[\n  999\n]");
+        assert_eq!(format(&"1234567".into(), 2), "1234567");
+    }
+
+    #[test]
+    fn test_indent_width() {
+        let opts = FormatOptions {
+            indent: 4,
+            ..Default::default()
+        };
+        let json = r#"{"key":[1]}"#;
+        let formatted = format_with(&json.into(), opts.step(), &opts);
+        assert_eq!(formatted, "{\n    \"key\": [\n        1\n    ]\n}");
+    }
+
+    #[test]
+    fn test_color() {
+        let opts = FormatOptions {
+            color: true,
+            ..Default::default()
+        };
+        let value = Value::from(r#"{"a": ["x", 1, true, null]}"#);
+        assert_eq!(
+            value.to_pretty_string_with(&opts),
+            "{\n  \x1b[1;34m\"a\"\x1b[0m: [\n    \x1b[32m\"x\"\x1b[0m,\n    \x1b[36m1\x1b[0m,\n    \
+             \x1b[35mtrue\x1b[0m,\n    \x1b[35mnull\x1b[0m\n  ]\n}"
+        );
+        assert_eq!(
+            Value::from("[]").to_pretty_string_with(&opts),
+            "[]",
+            "punctuation is not colored"
+        );
+    }
+
+    #[test]
+    fn test_tabs() {
+        let opts = FormatOptions {
+            indent: 4,
+            use_tabs: true,
+            ..Default::default()
+        };
+        let json = r#"{"key":[1]}"#;
+        let formatted = format_with(&json.into(), opts.step(), &opts);
+        assert_eq!(formatted, "{\n\t\"key\": [\n\t\t1\n\t]\n}");
+    }
+}
+
+#[cfg(test)]
+mod roundtrip_tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::Map;
+
+    fn tricky_string() -> impl Strategy<Value = String> {
+        prop_oneof![any::<String>(), "[\"\\\\/\\x00-\\x1fa-zあ😀 ]{0,12}",]
+    }
+
+    fn json_value() -> impl Strategy<Value = Value> {
+        let leaf = prop_oneof![
+            Just(Value::Null),
+            any::<bool>().prop_map(Value::Boolean),
+            any::<f64>()
+                .prop_filter("JSON has no NaN or infinity", |n| n.is_finite())
+                .prop_map(Value::Number),
+            tricky_string().prop_map(Value::String),
+        ];
+        leaf.prop_recursive(4, 64, 8, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..8).prop_map(Value::Array),
+                prop::collection::vec((tricky_string(), inner), 0..8)
+                    .prop_map(|members| Value::Object(Map::from_iter(members))),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn compact_roundtrip(value in json_value()) {
+            prop_assert_eq!(crate::parse(&value.to_compact_string()), value);
+        }
+
+        #[test]
+        fn pretty_roundtrip(value in json_value()) {
+            prop_assert_eq!(crate::parse(&format(&value, 2)), value);
+        }
+    }
 }
@@ -0,0 +1,125 @@
+//! Conversions between [`Value`] and `serde_json::Value`.
+
+use serde_json::Number;
+
+use crate::Value;
+
+/// Integers that an `f64` cannot hold exactly are kept as
+/// [`Value::RawNumber`] so that no digits are lost.
+impl From<serde_json::Value> for Value {
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Boolean(b),
+            serde_json::Value::Number(n) => match n.as_f64() {
+                Some(f) if n.is_f64() || f as i128 == integer(&n) => Value::Number(f),
+                _ => Value::RawNumber(n.to_string()),
+            },
+            serde_json::Value::String(s) => Value::String(s),
+            serde_json::Value::Array(arr) => {
+                Value::Array(arr.into_iter().map(Value::from).collect())
+            }
+            serde_json::Value::Object(obj) => {
+                Value::Object(obj.into_iter().map(|(k, v)| (k, Value::from(v))).collect())
+            }
+        }
+    }
+}
+
+fn integer(n: &Number) -> i128 {
+    n.as_i64()
+        .map(i128::from)
+        .or_else(|| n.as_u64().map(i128::from))
+        .unwrap_or_default()
+}
+
+/// Whole numbers that fit in an `i64` or `u64` become serde_json integers.
+/// Non-finite numbers have no JSON form and become `null`, as they do when
+/// serializing.
+impl From<Value> for serde_json::Value {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => serde_json::Value::Null,
+            Value::Boolean(b) => serde_json::Value::Bool(b),
+            Value::Number(n) => number(n),
+            Value::RawNumber(s) => {
+                if let Ok(i) = s.parse::<i64>() {
+                    serde_json::Value::from(i)
+                } else if let Ok(u) = s.parse::<u64>() {
+                    serde_json::Value::from(u)
+                } else {
+                    number(s.parse().unwrap_or(f64::NAN))
+                }
+            }
+            Value::String(s) => serde_json::Value::String(s),
+            Value::Array(arr) => {
+                serde_json::Value::Array(arr.into_iter().map(serde_json::Value::from).collect())
+            }
+            Value::Object(obj) => serde_json::Value::Object(
+                obj.into_iter()
+                    .map(|(k, v)| (k.to_string(), serde_json::Value::from(v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+fn number(n: f64) -> serde_json::Value {
+    if n.fract() == 0.0 && n >= i64::MIN as f64 && n < i64::MAX as f64 {
+        serde_json::Value::from(n as i64)
+    } else if n.fract() == 0.0 && n >= 0.0 && n < u64::MAX as f64 {
+        serde_json::Value::from(n as u64)
+    } else {
+        Number::from_f64(n).map_or(serde_json::Value::Null, serde_json::Value::Number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn round_trip_nested() {
+        let original = json!({
+            "Image": {
+                "Width": 800,
+                "Title": "View from 15th Floor",
+                "Ratio": 1.5,
+                "Animated": false,
+                "Thumbnail": null,
+                "IDs": [116, 943, -234, 38793]
+            }
+        });
+        let value = Value::from(original.clone());
+        assert_eq!(
+            value,
+            crate::parse(
+                r#"{"Image": {"Width": 800, "Title": "View from 15th Floor", "Ratio": 1.5,
+                    "Animated": false, "Thumbnail": null, "IDs": [116, 943, -234, 38793]}}"#
+            )
+        );
+        assert_eq!(serde_json::Value::from(value), original);
+    }
+
+    #[test]
+    fn numbers() {
+        // Whole numbers come back as integers, not `800.0`.
+        assert_eq!(serde_json::Value::from(Value::Number(800.0)), json!(800));
+        assert_eq!(serde_json::Value::from(Value::Number(-0.5)), json!(-0.5));
+        assert_eq!(
+            serde_json::Value::from(Value::Number(f64::NAN)),
+            json!(null)
+        );
+
+        // Integers beyond 2^53 keep their digits in both directions.
+        let big = Value::from(json!(u64::MAX));
+        assert_eq!(big, Value::RawNumber("18446744073709551615".into()));
+        assert_eq!(serde_json::Value::from(big), json!(u64::MAX));
+        assert_eq!(
+            serde_json::Value::from(Value::RawNumber("1.25e2".into())),
+            json!(125)
+        );
+    }
+}
@@ -0,0 +1,519 @@
+//! Scanning of JSON tokens, shared by the parser and exposed for consumers
+//! that build their own structures instead of a [`Value`](crate::Value).
+//!
+//! ```
+//! use rj::lexer::{Token, Tokenizer};
+//!
+//! let tokens: Vec<Token> = Tokenizer::new(r#"{"a": [1, true]}"#)
+//!     .collect::<Result<_, _>>()
+//!     .unwrap();
+//! assert_eq!(tokens.len(), 9);
+//! assert_eq!(tokens[1], Token::String("a".into()));
+//! ```
+
+use std::{borrow::Cow, str::CharIndices};
+
+use crate::{
+    ErrorKind, ParseError, ParseOptions,
+    parse::{SyntaxError, error, locate, truncated},
+};
+
+/// A JSON token, borrowing from the input where it can.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token<'a> {
+    /// `{`
+    BeginObject,
+    /// `}`
+    EndObject,
+    /// `[`
+    BeginArray,
+    /// `]`
+    EndArray,
+    /// `:` between an object key and its value.
+    NameSeparator,
+    /// `,` between members or elements.
+    ValueSeparator,
+    /// A string with its escapes decoded.
+    String(Cow<'a, str>),
+    /// The source text of a number, e.g. `-1.5e3`, for the caller to convert.
+    Number(&'a str),
+    True,
+    False,
+    Null,
+}
+
+/// Splits strict RFC 8259 JSON text into [`Token`]s, skipping whitespace.
+///
+/// Only single tokens are checked, not how they are arranged, so `]1:` is
+/// tokenized without complaint. After the first error the iterator ends.
+#[derive(Debug)]
+pub struct Tokenizer<'a> {
+    input: &'a str,
+    rest: &'a str,
+    options: ParseOptions,
+    /// Decoding buffer for strings with escapes.
+    scratch: String,
+    failed: bool,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Tokenizer {
+            input,
+            rest: input.strip_prefix('\u{FEFF}').unwrap_or(input),
+            options: ParseOptions::default(),
+            scratch: String::new(),
+            failed: false,
+        }
+    }
+
+    /// Byte offset just past the last token returned.
+    pub fn offset(&self) -> usize {
+        self.input.len() - self.rest.len()
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        self.rest = skip_trivia(self.rest, &self.options);
+        if self.rest.is_empty() {
+            return None;
+        }
+        match token(self.rest, &self.options, &mut self.scratch) {
+            Ok((token, rest)) => {
+                self.rest = rest;
+                Some(Ok(token))
+            }
+            Err(e) => {
+                self.failed = true;
+                Some(Err(locate(self.input, e)))
+            }
+        }
+    }
+}
+
+/// Scans the token at the very start of `input`, where the parser and
+/// [`Tokenizer`] both get their tokens from. Strings are decoded, using
+/// `scratch` for those with escapes; numbers are left as source text.
+///
+/// Inlined so that the parser, which calls it for every value, stays as fast
+/// as when it scanned values itself.
+#[inline]
+pub(crate) fn token<'a>(
+    input: &'a str,
+    opts: &ParseOptions,
+    scratch: &mut String,
+) -> Result<(Token<'a>, &'a str), SyntaxError> {
+    let (token, rest) = match input.as_bytes().first() {
+        Some(b'{') => (Token::BeginObject, &input[1..]),
+        Some(b'}') => (Token::EndObject, &input[1..]),
+        Some(b'[') => (Token::BeginArray, &input[1..]),
+        Some(b']') => (Token::EndArray, &input[1..]),
+        Some(b':') => (Token::NameSeparator, &input[1..]),
+        Some(b',') => (Token::ValueSeparator, &input[1..]),
+        Some(&quote) if quote == b'"' || quote == b'\'' && opts.allow_single_quotes => {
+            let (s, rest) = string(input, opts, scratch)?;
+            (Token::String(s), rest)
+        }
+        Some(b'-' | b'0'..=b'9') => {
+            let (text, rest) = number(input)?;
+            (Token::Number(text), rest)
+        }
+        _ => return literal(input),
+    };
+    Ok((token, rest))
+}
+
+/// Scans `true`, `false` or `null`, the only tokens left once punctuation,
+/// strings and numbers are ruled out.
+fn literal(input: &str) -> Result<(Token<'_>, &str), SyntaxError> {
+    for (word, token) in [
+        ("true", Token::True),
+        ("false", Token::False),
+        ("null", Token::Null),
+    ] {
+        if let Some(rest) = input.strip_prefix(word) {
+            return Ok((token, rest));
+        }
+    }
+
+    let literal_prefix =
+        |l: &str| !input.is_empty() && l.len() > input.len() && l.starts_with(input);
+    if ["true", "false", "null"].into_iter().any(literal_prefix) {
+        return Err(truncated(input, format!("Unexpected token: '{}'", input)));
+    }
+    match input.chars().next() {
+        Some(c) => Err(error(input, format!("Unexpected token: '{}'", c))),
+        None => Err(truncated(input, "Unexpected end of input.")),
+    }
+}
+
+/// whitespace = \x20 \x09 \x0a \x0d
+/// - \x20 space
+/// - \x09 horizontal tab
+/// - \x0a line feed or new line
+/// - \x0d carriage return
+pub(crate) fn is_whitespace(c: char) -> bool {
+    c == '\x20' || c == '\x09' || c == '\x0a' || c == '\x0d'
+}
+
+pub(crate) fn eat_whitespace(input: &str) -> &str {
+    let mut pos = 0;
+    for c in input.chars() {
+        if !is_whitespace(c) {
+            break;
+        }
+        pos += c.len_utf8(); // Advance by byte length of char
+    }
+    &input[pos..]
+}
+
+/// Skips whitespace and, if enabled, line comments.
+pub(crate) fn skip_trivia<'a>(input: &'a str, opts: &ParseOptions) -> &'a str {
+    let mut rest = eat_whitespace(input);
+    while opts.allow_line_comments && rest.starts_with("//") {
+        let end = rest.find('\n').unwrap_or(rest.len());
+        rest = eat_whitespace(&rest[end..]);
+    }
+    rest
+}
+
+/// Splits a leading `[A-Za-z_$][A-Za-z0-9_$]*` identifier off `input`.
+pub(crate) fn identifier(input: &str) -> Option<(&str, &str)> {
+    let is_start = |c: char| c.is_ascii_alphabetic() || c == '_' || c == '$';
+    if !input.starts_with(is_start) {
+        return None;
+    }
+    let end = input
+        .find(|c: char| !(is_start(c) || c.is_ascii_digit()))
+        .unwrap_or(input.len());
+    Some(input.split_at(end))
+}
+
+/// Scans a quoted string. Strings without escapes are borrowed from `input`;
+/// the first escape copies what was read so far into `scratch` and decodes
+/// from there on.
+pub(crate) fn string<'a>(
+    input: &'a str,
+    opts: &ParseOptions,
+    scratch: &mut String,
+) -> Result<(Cow<'a, str>, &'a str), SyntaxError> {
+    let mut chars = input.char_indices(); // Iterator that yields (byte_index, char)
+    let mut escaped = false;
+
+    // Expect the opening quote; the string ends at the same kind of quote.
+    let quote = match chars.next() {
+        Some((_, '"')) => '"',
+        Some((_, '\'')) if opts.allow_single_quotes => '\'',
+        Some((_, c)) => {
+            return Err(error(
+                input,
+                format!("String must start with '\"'. Found '{}'.", c),
+            ));
+        }
+        None => {
+            return Err(error(
+                input,
+                "String must start with '\"'. Input was empty.",
+            ));
+        }
+    };
+
+    loop {
+        let Some((idx, c)) = chars.next() else {
+            return Err(error(
+                &input[input.len()..],
+                format!("Unterminated string: missing closing '{quote}'."),
+            ));
+        };
+
+        match c {
+            _ if c == quote => {
+                let parsed = if escaped {
+                    // Cloning allocates exactly the decoded length.
+                    Cow::Owned(scratch.clone())
+                } else {
+                    // Both quote characters are one byte long.
+                    Cow::Borrowed(&input[1..idx])
+                };
+                // The byte index *after* the closing quote.
+                return Ok((parsed, &input[idx + 1..]));
+            }
+            '\\' => {
+                let parsed_string = &mut *scratch;
+                if !escaped {
+                    escaped = true;
+                    parsed_string.clear();
+                    parsed_string.push_str(&input[1..idx]);
+                }
+                // Handle escape sequence
+                let Some((_, escaped_char)) = chars.next() else {
+                    return Err(truncated(
+                        &input[idx..],
+                        "Invalid escape sequence: '\\' at end of string.",
+                    ));
+                };
+
+                match escaped_char {
+                    '"' => parsed_string.push('"'),                    // quotation mark
+                    '\'' if quote == '\'' => parsed_string.push('\''), // apostrophe
+                    '\\' => parsed_string.push('\\'),                  // reverse solidus
+                    '/' => parsed_string.push('/'),                    // solidus
+                    'b' => parsed_string.push('\x08'),                 // backspace
+                    'f' => parsed_string.push('\x0C'),                 // form feed
+                    'n' => parsed_string.push('\n'),                   // line feed
+                    'r' => parsed_string.push('\r'),                   // carriage return
+                    't' => parsed_string.push('\t'),                   // tab
+                    'u' => {
+                        let unicode_char = decode_unicode_escape(&mut chars, &input[idx..])?;
+                        parsed_string.push(unicode_char);
+                    }
+                    _ => {
+                        let message = format!("Invalid escape sequence: '\\{}'", escaped_char);
+                        return Err(
+                            error(&input[idx..], message).kind(ErrorKind::InvalidEscape {
+                                escape: escaped_char,
+                            }),
+                        );
+                    }
+                }
+            }
+            // JSON strings cannot contain unescaped control characters like newlines or tabs
+            _ if c < '\x20' => {
+                return Err(SyntaxError {
+                    show_offset: true,
+                    ..error(
+                        &input[idx..],
+                        format!("unescaped control character U+{:04X}", c as u32),
+                    )
+                });
+            }
+            _ => {
+                // Regular character
+                if escaped {
+                    scratch.push(c);
+                }
+            }
+        }
+    }
+}
+
+/// Decodes the code point of a `\uXXXX` escape after its `\u` was consumed
+/// from `chars`. `escape` is the input starting at the backslash.
+///
+/// A high surrogate must be directly followed by a `\uXXXX` low surrogate,
+/// and the pair is combined into one supplementary-plane character.
+fn decode_unicode_escape(chars: &mut CharIndices, escape: &str) -> Result<char, SyntaxError> {
+    let high = hex4(chars, escape)?;
+    let code = match high {
+        0xD800..=0xDBFF => {
+            match (chars.next(), chars.next()) {
+                (Some((_, '\\')), Some((_, 'u'))) => {}
+                (None, _) | (Some((_, '\\')), None) => {
+                    return Err(truncated(
+                        escape,
+                        "Unpaired high surrogate in unicode escape.",
+                    ));
+                }
+                _ => {
+                    return Err(unicode_error(
+                        escape,
+                        "Unpaired high surrogate in unicode escape.",
+                    ));
+                }
+            }
+            let low = hex4(chars, escape)?;
+            if !(0xDC00..=0xDFFF).contains(&low) {
+                return Err(unicode_error(
+                    escape,
+                    "Unpaired high surrogate in unicode escape.",
+                ));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        }
+        0xDC00..=0xDFFF => {
+            return Err(unicode_error(
+                escape,
+                "Unexpected low surrogate in unicode escape.",
+            ));
+        }
+        _ => high,
+    };
+    char::from_u32(code).ok_or_else(|| unicode_error(escape, "Invalid unicode scalar value."))
+}
+
+const HEX4_EXPECTED: &str = "Invalid unicode escape sequence: expected 4 hex digits after '\\u'.";
+
+fn unicode_error(escape: &str, message: &str) -> SyntaxError {
+    error(escape, message).kind(ErrorKind::InvalidUnicode)
+}
+
+/// Reads the four hex digits of a `\uXXXX` escape.
+fn hex4(chars: &mut CharIndices, escape: &str) -> Result<u32, SyntaxError> {
+    let mut hex_val: u32 = 0;
+    for _ in 0..4 {
+        match chars.next() {
+            Some((_, '"')) => {
+                return Err(unicode_error(escape, HEX4_EXPECTED));
+            }
+            None => return Err(truncated(escape, HEX4_EXPECTED)),
+            Some((_, c)) => {
+                let digit = c
+                    .to_digit(16)
+                    .ok_or_else(|| unicode_error(escape, "Invalid hex digit in unicode escape."))?;
+                hex_val = (hex_val << 4) | digit;
+            }
+        }
+    }
+    Ok(hex_val)
+}
+
+/// Scans the longest prefix of `input` matching the RFC 8259 number grammar,
+/// `-? (0 | [1-9][0-9]*) (. [0-9]+)? ([eE] [+-]? [0-9]+)?`, and splits it
+/// off, leaving anything after it, e.g. the `.3` of `1.2.3`, to the caller.
+/// `f64` parsing accepts all text matching the grammar.
+fn number(input: &str) -> Result<(&str, &str), SyntaxError> {
+    let bytes = input.as_bytes();
+    let digits_end = |from: usize| {
+        from + bytes[from.min(bytes.len())..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+    let invalid = |end: usize| {
+        let message = format!("Invalid number: '{}'", &input[..end]);
+        if end == input.len() {
+            truncated(input, message)
+        } else {
+            error(input, message).kind(ErrorKind::InvalidNumber)
+        }
+    };
+
+    let int_start = usize::from(input.starts_with('-'));
+    let mut end = digits_end(int_start);
+    if end == int_start {
+        return Err(invalid(int_start));
+    }
+    if bytes[int_start] == b'0' && end > int_start + 1 {
+        let message = "Leading zeros are not allowed in numbers.";
+        return Err(error(&input[int_start..], message).kind(ErrorKind::InvalidNumber));
+    }
+    if bytes.get(end) == Some(&b'.') {
+        let frac_end = digits_end(end + 1);
+        if frac_end == end + 1 {
+            return Err(invalid(end + 1));
+        }
+        end = frac_end;
+    }
+    if let Some(b'e' | b'E') = bytes.get(end) {
+        let mut exp_start = end + 1;
+        if let Some(b'+' | b'-') = bytes.get(exp_start) {
+            exp_start += 1;
+        }
+        end = digits_end(exp_start);
+        if end == exp_start {
+            return Err(invalid(exp_start));
+        }
+    }
+
+    let (text, rest) = input.split_at(end);
+    // A letter glued to the digits (`123abc`, `1true`) is never a separate token.
+    if let Some(c) = rest.chars().next().filter(|c| c.is_alphanumeric()) {
+        return Err(error(
+            rest,
+            format!("Unexpected character '{}' after number.", c),
+        ));
+    }
+    Ok((text, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(input: &str) -> Vec<Token<'_>> {
+        Tokenizer::new(input).collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn tokenize_object() {
+        assert_eq!(
+            tokens(r#"{"a":1}"#),
+            [
+                Token::BeginObject,
+                Token::String("a".into()),
+                Token::NameSeparator,
+                Token::Number("1"),
+                Token::EndObject,
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_all_kinds() {
+        assert_eq!(
+            tokens(" [ -1.5e3, \"a\\nb\", true, false, null ] "),
+            [
+                Token::BeginArray,
+                Token::Number("-1.5e3"),
+                Token::ValueSeparator,
+                Token::String("a\nb".into()),
+                Token::ValueSeparator,
+                Token::True,
+                Token::ValueSeparator,
+                Token::False,
+                Token::ValueSeparator,
+                Token::Null,
+                Token::EndArray,
+            ]
+        );
+        assert!(tokens("").is_empty());
+        assert_eq!(
+            tokens("]1:"),
+            [Token::EndArray, Token::Number("1"), Token::NameSeparator]
+        );
+    }
+
+    #[test]
+    fn errors_end_iteration() {
+        let mut tokenizer = Tokenizer::new("[1, @]");
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BeginArray)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number("1"))));
+        assert_eq!(tokenizer.offset(), 2);
+        assert_eq!(tokenizer.next(), Some(Ok(Token::ValueSeparator)));
+        let err = tokenizer.next().unwrap().unwrap_err();
+        assert_eq!(err.message(), "Unexpected token: '@'");
+        assert_eq!(err.column(), 5);
+        assert_eq!(tokenizer.next(), None);
+    }
+
+    #[test]
+    fn errors_match_the_parser() {
+        for input in [
+            "tru", "nul", "01", "1.", "-", "\"a\\x\"", "\"\\u12", "@", "1e",
+        ] {
+            let err = Tokenizer::new(input).find_map(Result::err).unwrap();
+            assert_eq!(Err(err), crate::try_parse(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn strings_without_escapes_are_borrowed() {
+        let opts = ParseOptions::default();
+        let mut scratch = String::new();
+        let Ok((Cow::Borrowed(s), rest)) = string(r#""héllo" tail"#, &opts, &mut scratch) else {
+            panic!("expected a borrowed string");
+        };
+        assert_eq!((s, rest), ("héllo", " tail"));
+
+        let Ok((Cow::Owned(s), _)) = string(r#""hé\tllo\u0021""#, &opts, &mut scratch) else {
+            panic!("expected a decoded string");
+        };
+        assert_eq!(s, "hé\tllo!");
+    }
+}
@@ -1,19 +1,152 @@
 // Defined in RFC8259 also known as STD90.
 
-use value::Value;
+pub use borrowed::BorrowedValue;
+pub use builder::StrictObjectBuilder;
+#[cfg(feature = "comments")]
+pub use comments::Commented;
+pub use error::{Error, ErrorKind, ParseError, PathSegment};
+pub use generate::FormatOptions;
+pub use map::Map;
+pub use merge::{ArrayMergeStrategy, Conflict};
+pub use parse::{ParseOptions, Parser, ValueStream, Warning};
+pub use patch::PatchError;
+pub use pointer::PointerError;
+pub use schema::ValidationError;
+pub use spanned::Spanned;
+pub use value::{Value, ValueKind};
 
+mod borrowed;
+mod builder;
+#[cfg(feature = "comments")]
+mod comments;
+#[cfg(feature = "decimal")]
+mod decimal;
+mod error;
+mod flatten;
 mod generate;
+#[cfg(feature = "serde_json")]
+mod interop;
+pub mod lexer;
+pub mod map;
+mod merge;
 mod parse;
+mod patch;
+mod pointer;
+mod schema;
+mod snippet;
+mod sort;
+mod spanned;
+mod split;
 mod value;
+mod walk;
 
 pub fn parse(input: &str) -> Value {
     parse::parse(input)
 }
 
+/// Parses `input`, returning an error instead of panicking on invalid JSON.
+pub fn try_parse(input: &str) -> Result<Value, ParseError> {
+    parse::try_parse(input)
+}
+
+/// Like [`try_parse`], with parsing behavior controlled by `options`.
+pub fn try_parse_with(input: &str, options: &ParseOptions) -> Result<Value, ParseError> {
+    parse::try_parse_with(input, options)
+}
+
+/// Parses `input` like [`try_parse`] into a [`BorrowedValue`], whose
+/// strings and keys borrow from `input` unless they contain escapes.
+pub fn parse_borrowed(input: &str) -> Result<BorrowedValue<'_>, ParseError> {
+    parse::parse_borrowed(input)
+}
+
+/// Parses `input` like [`try_parse`], also collecting [`Warning`]s about
+/// valid input that may not mean what it says, such as integers beyond
+/// 2^53 that lose precision as `f64`.
+pub fn parse_with_warnings(input: &str) -> (Result<Value, ParseError>, Vec<Warning>) {
+    parse::parse_with_warnings(input)
+}
+
+/// Parses the first JSON value of `input`, which may be cut off, e.g. a
+/// buffer of data read from a socket so far.
+///
+/// Returns the value with the number of bytes it took up, so the caller can
+/// drop them and parse the rest later, or the error with zero bytes taken.
+/// An error of kind [`ErrorKind::UnexpectedEof`] means that the input may
+/// become valid once more of it arrives. A number running to the end of the
+/// input counts as cut off, as more digits may follow.
+pub fn parse_partial(input: &str) -> (Result<Value, ParseError>, usize) {
+    parse::parse_partial(input)
+}
+
+/// Parses a sequence of JSON values separated by optional whitespace, such
+/// as newline-delimited JSON. See [`ValueStream`] to parse them lazily.
+pub fn parse_many(input: &str) -> Result<Vec<Value>, ParseError> {
+    parse::parse_many(input)
+}
+
+/// Parses UTF-8 encoded bytes, such as a file read with `std::fs::read`.
+/// Invalid UTF-8 is reported at the offset of the first bad byte.
+pub fn parse_bytes(input: &[u8]) -> Result<Value, ParseError> {
+    parse::parse_bytes(input)
+}
+
+/// Parses `input` like [`try_parse`], also recording the byte range each
+/// value was parsed from, for diagnostics that point into the source.
+pub fn parse_spanned(input: &str) -> Result<Spanned<Value>, ParseError> {
+    parse::parse_spanned(input)
+}
+
+/// Parses `input` allowing `//` line comments and keeps the comments above
+/// object members, so that they can be written back after editing.
+#[cfg(feature = "comments")]
+pub fn parse_commented(input: &str) -> Result<Commented, ParseError> {
+    comments::parse_commented(input)
+}
+
 pub fn stringify(value: &Value) -> String {
     value.to_string()
 }
 
+/// Serializes `value` as indented JSON, like [`format`] does for text.
+pub fn stringify_pretty(value: &Value) -> String {
+    value.to_pretty_string()
+}
+
+/// Like [`stringify_pretty`], with output controlled by `options`.
+pub fn stringify_pretty_with(value: &Value, options: &FormatOptions) -> String {
+    value.to_pretty_string_with(options)
+}
+
 pub fn format(input: &str) -> String {
     generate::format(&parse(input), 2)
 }
+
+/// Like [`format`], with output controlled by `options`.
+pub fn format_with(input: &str, options: &FormatOptions) -> String {
+    parse(input).to_pretty_string_with(options)
+}
+
+/// Checks `value` against a JSON Schema, supporting only the `type`,
+/// `required`, `properties`, `items`, `enum`, `minimum` and `maximum`
+/// keywords. Other keywords are ignored.
+pub fn validate(value: &Value, schema: &Value) -> Result<(), Vec<ValidationError>> {
+    schema::validate(value, schema)
+}
+
+/// Escapes `~` as `~0` and `/` as `~1`, so that an object key can be used
+/// as a token of a JSON Pointer, e.g. `format!("/{}", escape_pointer_token(key))`.
+pub fn escape_pointer_token(key: &str) -> String {
+    pointer::escape_token(key)
+}
+
+/// Turns a JSON Pointer token back into the object key it stands for,
+/// failing on a `~` not followed by `0` or `1`.
+pub fn unescape_pointer_token(token: &str) -> Result<String, PointerError> {
+    pointer::try_unescape_token(token)
+}
+
+/// Returns the source slice of every top-level document in `input`.
+pub fn split_documents(input: &str) -> Result<Vec<&str>, ParseError> {
+    split::split_documents(input)
+}
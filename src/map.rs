@@ -0,0 +1,420 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::Value;
+
+/// Objects with at most this many members are searched linearly, which is
+/// faster than hashing for the small objects most documents consist of.
+const LINEAR_MAX: usize = 8;
+
+/// The members of a [`Value::Object`], kept in insertion order.
+///
+/// The API follows `HashMap<String, Value>`. Inserting an existing key
+/// replaces its value in place, and removing a member keeps the order of
+/// the rest. Equality ignores order, as JSON objects are unordered.
+///
+/// Keys are stored as `Arc<str>`, so that objects can share the storage of
+/// identical keys, see [`ParseOptions::intern_keys`](crate::ParseOptions::intern_keys).
+/// Methods taking a key accept a `String` or `&str` as well.
+#[derive(Clone, Default)]
+pub struct Map {
+    entries: Vec<(Arc<str>, Value)>,
+    /// Positions of the keys in `entries`, only kept once there are more
+    /// than [`LINEAR_MAX`] of them.
+    index: HashMap<Arc<str>, usize>,
+}
+
+pub type Iter<'a> = std::iter::Map<
+    std::slice::Iter<'a, (Arc<str>, Value)>,
+    fn(&'a (Arc<str>, Value)) -> (&'a str, &'a Value),
+>;
+pub type IterMut<'a> = std::iter::Map<
+    std::slice::IterMut<'a, (Arc<str>, Value)>,
+    fn(&'a mut (Arc<str>, Value)) -> (&'a str, &'a mut Value),
+>;
+
+impl Map {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Map {
+            entries: Vec::with_capacity(capacity),
+            index: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        if self.entries.len() <= LINEAR_MAX {
+            self.entries.iter().position(|(k, _)| **k == *key)
+        } else {
+            self.index.get(key).copied()
+        }
+    }
+
+    fn reindex(&mut self) {
+        self.index.clear();
+        if self.entries.len() > LINEAR_MAX {
+            let positions = self.entries.iter().enumerate();
+            self.index
+                .extend(positions.map(|(i, (k, _))| (k.clone(), i)));
+        }
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.position(key).is_some()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.position(key).map(|i| &self.entries[i].1)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.position(key).map(|i| &mut self.entries[i].1)
+    }
+
+    /// Inserts a member, returning the previous value of an existing key,
+    /// which keeps its position.
+    pub fn insert(&mut self, key: impl Into<Arc<str>>, value: Value) -> Option<Value> {
+        let key = key.into();
+        if let Some(i) = self.position(&key) {
+            return Some(std::mem::replace(&mut self.entries[i].1, value));
+        }
+        self.push(key, value);
+        None
+    }
+
+    /// Appends a member whose key is known to be absent.
+    fn push(&mut self, key: Arc<str>, value: Value) -> usize {
+        let i = self.entries.len();
+        if i == LINEAR_MAX {
+            self.entries.push((key, value));
+            self.reindex();
+        } else {
+            if i > LINEAR_MAX {
+                self.index.insert(key.clone(), i);
+            }
+            self.entries.push((key, value));
+        }
+        i
+    }
+
+    /// Removes a member, shifting the members after it to keep their order.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let i = self.position(key)?;
+        let (_, value) = self.entries.remove(i);
+        self.reindex();
+        Some(value)
+    }
+
+    /// Keeps only the members for which `f` returns `true`.
+    pub fn retain(&mut self, mut f: impl FnMut(&str, &mut Value) -> bool) {
+        self.entries.retain_mut(|(k, v)| f(k, v));
+        self.reindex();
+    }
+
+    pub fn entry(&mut self, key: impl Into<Arc<str>>) -> Entry<'_> {
+        let key = key.into();
+        match self.position(&key) {
+            Some(index) => Entry::Occupied(OccupiedEntry { map: self, index }),
+            None => Entry::Vacant(VacantEntry { map: self, key }),
+        }
+    }
+
+    /// Removes all members, returning them in order.
+    pub fn drain(&mut self) -> std::vec::Drain<'_, (Arc<str>, Value)> {
+        self.index.clear();
+        self.entries.drain(..)
+    }
+
+    /// Sorts the members by key, comparing code points.
+    pub fn sort_keys(&mut self) {
+        self.entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        self.reindex();
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        self.entries.iter().map(|(k, v)| (&**k, v))
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        self.entries.iter_mut().map(|(k, v)| (&**k, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(k, _)| &**k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Value> {
+        self.entries.iter_mut().map(|(_, v)| v)
+    }
+}
+
+impl std::fmt::Debug for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for Map {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl std::ops::Index<&str> for Map {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        self.get(key).expect("no member with this key")
+    }
+}
+
+impl<K: Into<Arc<str>>> FromIterator<(K, Value)> for Map {
+    fn from_iter<I: IntoIterator<Item = (K, Value)>>(iter: I) -> Self {
+        let mut map = Map::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Into<Arc<str>>> Extend<(K, Value)> for Map {
+    fn extend<I: IntoIterator<Item = (K, Value)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K: Into<Arc<str>>, const N: usize> From<[(K, Value); N]> for Map {
+    fn from(members: [(K, Value); N]) -> Self {
+        members.into_iter().collect()
+    }
+}
+
+impl IntoIterator for Map {
+    type Item = (Arc<str>, Value);
+    type IntoIter = std::vec::IntoIter<(Arc<str>, Value)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Map {
+    type Item = (&'a str, &'a Value);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Map {
+    type Item = (&'a str, &'a mut Value);
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// A member of a [`Map`] that may or may not exist, from [`Map::entry`].
+pub enum Entry<'a> {
+    Occupied(OccupiedEntry<'a>),
+    Vacant(VacantEntry<'a>),
+}
+
+pub struct OccupiedEntry<'a> {
+    map: &'a mut Map,
+    index: usize,
+}
+
+pub struct VacantEntry<'a> {
+    map: &'a mut Map,
+    key: Arc<str>,
+}
+
+impl<'a> Entry<'a> {
+    pub fn key(&self) -> &str {
+        match self {
+            Entry::Occupied(e) => e.key(),
+            Entry::Vacant(e) => e.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: Value) -> &'a mut Value {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with(self, default: impl FnOnce() -> Value) -> &'a mut Value {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut Value {
+        self.or_insert_with(Value::default)
+    }
+
+    pub fn and_modify(mut self, f: impl FnOnce(&mut Value)) -> Self {
+        if let Entry::Occupied(e) = &mut self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a> OccupiedEntry<'a> {
+    pub fn key(&self) -> &str {
+        &self.map.entries[self.index].0
+    }
+
+    pub fn get(&self) -> &Value {
+        &self.map.entries[self.index].1
+    }
+
+    pub fn get_mut(&mut self) -> &mut Value {
+        &mut self.map.entries[self.index].1
+    }
+
+    pub fn into_mut(self) -> &'a mut Value {
+        &mut self.map.entries[self.index].1
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn insert(&mut self, value: Value) -> Value {
+        std::mem::replace(self.get_mut(), value)
+    }
+}
+
+impl<'a> VacantEntry<'a> {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn insert(self, value: Value) -> &'a mut Value {
+        let i = self.map.push(self.key, value);
+        &mut self.map.entries[i].1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(n: usize) -> Map {
+        (0..n)
+            .map(|i| (format!("k{i}"), Value::Number(i as f64)))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_insertion_order() {
+        let mut map = Map::new();
+        for k in ["b", "a", "c"] {
+            map.insert(k.to_string(), Value::Null);
+        }
+        assert_eq!(
+            map.insert("a".to_string(), Value::Boolean(true)),
+            Some(Value::Null)
+        );
+        assert_eq!(map.keys().collect::<Vec<_>>(), ["b", "a", "c"]);
+
+        assert_eq!(map.remove("b"), Some(Value::Null));
+        assert_eq!(map.remove("b"), None);
+        assert_eq!(map.keys().collect::<Vec<_>>(), ["a", "c"]);
+    }
+
+    #[test]
+    fn lookups_past_linear_limit() {
+        for n in [LINEAR_MAX - 1, LINEAR_MAX, LINEAR_MAX + 1, 3 * LINEAR_MAX] {
+            let mut map = numbered(n);
+            for i in 0..n {
+                assert_eq!(map.get(&format!("k{i}")), Some(&Value::Number(i as f64)));
+            }
+            assert!(!map.contains_key("missing"));
+
+            map.remove("k0");
+            map.retain(|k, _| k != "k2");
+            map.entry("new".to_string()).or_insert(Value::Null);
+            for (i, (k, _)) in map.iter().enumerate() {
+                assert_eq!(map.keys().nth(i), Some(k));
+                assert!(map.contains_key(k), "{k} of {n}");
+            }
+            assert_eq!(map.keys().last().unwrap(), "new");
+        }
+    }
+
+    #[test]
+    fn equality_ignores_order() {
+        let a: Map = [
+            ("x".to_string(), Value::Null),
+            ("y".to_string(), Value::Null),
+        ]
+        .into();
+        let b: Map = [
+            ("y".to_string(), Value::Null),
+            ("x".to_string(), Value::Null),
+        ]
+        .into();
+        assert_eq!(a, b);
+        assert_ne!(a, numbered(2));
+    }
+
+    #[test]
+    fn interned_keys_share_storage() {
+        let json = r#"[{"id": 1, "name": "a"}, {"name": "b", "id": 2}]"#;
+        let parsed_keys = |opts: &crate::ParseOptions| {
+            let v = crate::try_parse_with(json, opts).unwrap();
+            let Value::Array(records) = v else {
+                panic!("expected an array");
+            };
+            let key = |i: usize, k: &str| match &records[i] {
+                Value::Object(obj) => obj.entries[obj.position(k).unwrap()].0.clone(),
+                _ => panic!("expected an object"),
+            };
+            (key(0, "id"), key(1, "id"), key(1, "name"))
+        };
+
+        let (a, b, _) = parsed_keys(&crate::ParseOptions::default());
+        assert!(!Arc::ptr_eq(&a, &b));
+        let opts = crate::ParseOptions {
+            intern_keys: true,
+            ..Default::default()
+        };
+        let (a, b, name) = parsed_keys(&opts);
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!((&*a, &*name), ("id", "name"));
+    }
+
+    #[test]
+    fn entries() {
+        let mut map = numbered(1);
+        map.entry("k0".to_string())
+            .and_modify(|v| *v = Value::Boolean(true))
+            .or_default();
+        *map.entry("k1".to_string()).or_default() = Value::Number(1.0);
+        assert_eq!(map["k0"], Value::Boolean(true));
+        assert_eq!(map["k1"], Value::Number(1.0));
+
+        let Entry::Occupied(mut e) = map.entry("k0".to_string()) else {
+            panic!("k0 exists");
+        };
+        assert_eq!(e.key(), "k0");
+        assert_eq!(e.insert(Value::Null), Value::Boolean(true));
+    }
+}
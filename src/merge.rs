@@ -0,0 +1,308 @@
+use std::collections::HashSet;
+
+use crate::{Map, Value, pointer::escape_token};
+
+/// A location where both sides of a three-way merge changed the base
+/// differently. A side is `None` when it removed the value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// JSON Pointer to the conflicting value.
+    pub path: String,
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+/// How [`Value::deep_merge`] combines two arrays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayMergeStrategy {
+    /// The right-hand array replaces the left-hand one.
+    Replace,
+    /// The right-hand elements are appended.
+    Concat,
+    /// The right-hand elements not already present are appended.
+    Union,
+}
+
+impl Value {
+    /// Merges the edits of `ours` and `theirs` against their common `base`.
+    ///
+    /// Changes made on only one side are taken as is and objects are merged
+    /// member by member, in the order of `base` followed by the members
+    /// added on either side. Any other difference is reported as a
+    /// [`Conflict`].
+    pub fn three_way_merge(
+        base: &Value,
+        ours: &Value,
+        theirs: &Value,
+    ) -> Result<Value, Vec<Conflict>> {
+        let mut conflicts = Vec::new();
+        let merged = merge3(
+            Some(base),
+            Some(ours),
+            Some(theirs),
+            String::new(),
+            &mut conflicts,
+        );
+        if conflicts.is_empty() {
+            Ok(merged.unwrap_or(Value::Null))
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+impl Value {
+    /// Applies an RFC 7386 JSON Merge Patch.
+    ///
+    /// Object patches are merged member by member, where a `null` member
+    /// removes the key. Any other patch replaces the value entirely.
+    pub fn merge_patch(&mut self, patch: &Value) {
+        let Value::Object(patch) = patch else {
+            *self = patch.clone();
+            return;
+        };
+        if !matches!(self, Value::Object(_)) {
+            *self = Value::Object(Map::new());
+        }
+        if let Value::Object(target) = self {
+            for (k, v) in patch {
+                if *v == Value::Null {
+                    target.remove(k);
+                } else {
+                    target.entry(k).or_insert(Value::Null).merge_patch(v);
+                }
+            }
+        }
+    }
+}
+
+impl Value {
+    /// Recursively merges `other` into this value, e.g. to layer
+    /// configuration files.
+    ///
+    /// Objects are merged member by member and arrays as `array_strategy`
+    /// says. Any other pair, including `null` on the right, takes the
+    /// right-hand value; unlike [`Value::merge_patch`] nothing is removed.
+    pub fn deep_merge(&mut self, other: Value, array_strategy: ArrayMergeStrategy) {
+        match (self, other) {
+            (Value::Object(target), Value::Object(other)) => {
+                for (k, v) in other {
+                    match target.get_mut(&k) {
+                        Some(existing) => existing.deep_merge(v, array_strategy),
+                        None => {
+                            target.insert(k, v);
+                        }
+                    }
+                }
+            }
+            (Value::Array(target), Value::Array(other))
+                if array_strategy != ArrayMergeStrategy::Replace =>
+            {
+                for v in other {
+                    if array_strategy == ArrayMergeStrategy::Concat || !target.contains(&v) {
+                        target.push(v);
+                    }
+                }
+            }
+            (target, other) => *target = other,
+        }
+    }
+}
+
+impl Value {
+    /// Adds the members of `defaults` that this object lacks, recursing into
+    /// objects present on both sides, e.g. to fill in configuration.
+    ///
+    /// Existing values always win, including `null` and values of another
+    /// type than the default; only missing keys are added.
+    pub fn apply_defaults(&mut self, defaults: &Value) {
+        let (Value::Object(target), Value::Object(defaults)) = (self, defaults) else {
+            return;
+        };
+        for (k, default) in defaults {
+            match target.get_mut(k) {
+                Some(existing) => existing.apply_defaults(default),
+                None => {
+                    target.insert(k, default.clone());
+                }
+            }
+        }
+    }
+}
+
+fn merge3(
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    path: String,
+    conflicts: &mut Vec<Conflict>,
+) -> Option<Value> {
+    if ours == theirs || theirs == base {
+        return ours.cloned();
+    }
+    if ours == base {
+        return theirs.cloned();
+    }
+
+    let empty = Map::new();
+    if let (Some(Value::Object(o)), Some(Value::Object(t))) = (ours, theirs) {
+        let b = match base {
+            Some(Value::Object(b)) => b,
+            _ => &empty,
+        };
+        // Members keep the order of `base`, followed by those added by `ours`
+        // and then `theirs`.
+        let mut seen = HashSet::new();
+        let keys = b.keys().chain(o.keys()).chain(t.keys());
+        let mut merged = Map::new();
+        for key in keys.filter(|key| seen.insert(*key)) {
+            let path = format!("{path}/{}", escape_token(key));
+            if let Some(v) = merge3(b.get(key), o.get(key), t.get(key), path, conflicts) {
+                merged.insert(key, v);
+            }
+        }
+        return Some(Value::Object(merged));
+    }
+
+    conflicts.push(Conflict {
+        path,
+        base: base.cloned(),
+        ours: ours.cloned(),
+        theirs: theirs.cloned(),
+    });
+    ours.cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deep_merge_objects() {
+        let mut v = Value::from(r#"{"a": {"x": 1}}"#);
+        v.deep_merge(
+            Value::from(r#"{"a": {"y": 2}}"#),
+            ArrayMergeStrategy::Replace,
+        );
+        assert_eq!(v, Value::from(r#"{"a": {"x": 1, "y": 2}}"#));
+
+        let mut v = Value::from(r#"{"a": {"x": 1}, "b": [1], "c": "s"}"#);
+        v.deep_merge(
+            Value::from(r#"{"a": 2, "b": {"k": null}, "c": null, "d": []}"#),
+            ArrayMergeStrategy::Concat,
+        );
+        assert_eq!(
+            v,
+            Value::from(r#"{"a": 2, "b": {"k": null}, "c": null, "d": []}"#)
+        );
+    }
+
+    #[test]
+    fn apply_defaults_keeps_existing_values() {
+        let mut v = Value::from(r#"{"a":9}"#);
+        v.apply_defaults(&Value::from(r#"{"a":1,"b":2}"#));
+        assert_eq!(v, Value::from(r#"{"a":9,"b":2}"#));
+
+        let mut v = Value::from(r#"{"db": {"host": "x"}, "log": null, "tags": ["a"]}"#);
+        v.apply_defaults(&Value::from(
+            r#"{"db": {"host": "localhost", "port": 5432}, "log": {"level": "info"}, "tags": ["b"]}"#,
+        ));
+        assert_eq!(
+            v,
+            Value::from(r#"{"db": {"host": "x", "port": 5432}, "log": null, "tags": ["a"]}"#)
+        );
+
+        let mut v = Value::Number(1.0);
+        v.apply_defaults(&Value::from(r#"{"a": 1}"#));
+        assert_eq!(v, Value::Number(1.0));
+    }
+
+    #[test]
+    fn deep_merge_arrays() {
+        let base = Value::from(r#"{"tags": ["a", "b"], "n": [{"x": 1}]}"#);
+        let layer = Value::from(r#"{"tags": ["b", "c"], "n": [{"x": 1}]}"#);
+        let merged = |strategy| {
+            let mut v = base.clone();
+            v.deep_merge(layer.clone(), strategy);
+            v
+        };
+        assert_eq!(merged(ArrayMergeStrategy::Replace), layer);
+        assert_eq!(
+            merged(ArrayMergeStrategy::Concat),
+            Value::from(r#"{"tags": ["a", "b", "b", "c"], "n": [{"x": 1}, {"x": 1}]}"#)
+        );
+        assert_eq!(
+            merged(ArrayMergeStrategy::Union),
+            Value::from(r#"{"tags": ["a", "b", "c"], "n": [{"x": 1}]}"#)
+        );
+    }
+
+    #[test]
+    fn merge_changes_to_different_fields() {
+        let base = Value::from(r#"{"a": 1, "b": {"c": 2, "d": 3}, "e": 4}"#);
+        let ours = Value::from(r#"{"a": 10, "b": {"c": 2, "d": 3}, "e": 4}"#);
+        let theirs = Value::from(r#"{"a": 1, "b": {"c": 2, "d": 30}, "f": 5}"#);
+        assert_eq!(
+            Value::three_way_merge(&base, &ours, &theirs),
+            Ok(Value::from(r#"{"a": 10, "b": {"c": 2, "d": 30}, "f": 5}"#))
+        );
+    }
+
+    #[test]
+    fn merge_keeps_member_order() {
+        let base = Value::from(r#"{"z": 1, "m": 2, "a": 3}"#);
+        let ours = Value::from(r#"{"z": 1, "m": 2, "a": 3, "y": 4}"#);
+        let theirs = Value::from(r#"{"b": 5, "m": 2, "a": 30}"#);
+        let merged = Value::three_way_merge(&base, &ours, &theirs).unwrap();
+        assert_eq!(merged.to_compact_string(), r#"{"m":2,"a":30,"y":4,"b":5}"#);
+    }
+
+    #[test]
+    fn merge_conflict_on_same_field() {
+        let base = Value::from(r#"{"a": {"b/c": 1}, "d": 1}"#);
+        let ours = Value::from(r#"{"a": {"b/c": 2}, "d": 1}"#);
+        let theirs = Value::from(r#"{"a": {"b/c": 3}, "d": 2}"#);
+        assert_eq!(
+            Value::three_way_merge(&base, &ours, &theirs),
+            Err(vec![Conflict {
+                path: "/a/b~1c".to_string(),
+                base: Some(Value::Number(1.0)),
+                ours: Some(Value::Number(2.0)),
+                theirs: Some(Value::Number(3.0)),
+            }])
+        );
+    }
+
+    #[test]
+    fn merge_patch_deletes_null_members() {
+        let mut v = Value::from(r#"{"a": "b", "c": {"d": "e", "f": "g"}}"#);
+        v.merge_patch(&Value::from(r#"{"a": "z", "c": {"f": null}}"#));
+        assert_eq!(v, Value::from(r#"{"a": "z", "c": {"d": "e"}}"#));
+    }
+
+    #[test]
+    fn merge_patch_nested() {
+        let mut v = Value::from(r#"{"title": "Hello", "author": {"name": "x"}, "tags": ["a"]}"#);
+        v.merge_patch(&Value::from(
+            r#"{"author": {"email": "x@example.com"}, "tags": ["b"], "new": {"k": null, "n": 1}}"#,
+        ));
+        assert_eq!(
+            v,
+            Value::from(
+                r#"{"title": "Hello", "author": {"name": "x", "email": "x@example.com"}, "tags": ["b"], "new": {"n": 1}}"#
+            )
+        );
+    }
+
+    #[test]
+    fn merge_patch_replaces_non_objects() {
+        let mut v = Value::from(r#"{"a": "b"}"#);
+        v.merge_patch(&Value::from(r#"["c"]"#));
+        assert_eq!(v, Value::from(r#"["c"]"#));
+
+        let mut v = Value::from(r#"["a"]"#);
+        v.merge_patch(&Value::from(r#"{"a": "b"}"#));
+        assert_eq!(v, Value::from(r#"{"a": "b"}"#));
+    }
+}
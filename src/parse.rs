@@ -1,264 +1,679 @@
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    ops::Range,
+    sync::Arc,
+};
 
-use crate::Value;
+use crate::{
+    BorrowedValue, ErrorKind, Map, ParseError, PathSegment, Spanned, Value, ValueKind,
+    lexer::{Token, identifier, skip_trivia, string, token},
+};
 
-pub(crate) fn parse(input: &str) -> Value {
-    let (v, rest) = value(input);
-    // After parsing the top-level value, there should ideally be only whitespace left.
-    let rest = eat_whitespace(rest);
-    if !rest.is_empty() {
-        panic!("Unexpected characters after JSON value: '{}'", rest);
-    }
-    v
+/// A syntax error positioned by the amount of input left when it occurred,
+/// which lets the parsing functions work on plain `&str` remainders.
+pub(crate) struct SyntaxError {
+    pub(crate) remaining: usize,
+    pub(crate) message: String,
+    /// Whether the byte offset is appended to the message, for errors that are
+    /// hard to spot by line and column alone, e.g. in minified input.
+    pub(crate) show_offset: bool,
+    pub(crate) kind: ErrorKind,
+    /// Path to the value being parsed, innermost segment first, as it is
+    /// collected while the error propagates out of nested values.
+    pub(crate) path: Vec<PathSegment>,
 }
 
-fn value(input: &str) -> (Value, &str) {
-    let input = eat_whitespace(input);
+/// An error at the start of `rest`, of kind [`ErrorKind::UnexpectedToken`]
+/// for the character there unless [`SyntaxError::kind`] says otherwise.
+pub(crate) fn error(rest: &str, message: impl Into<String>) -> SyntaxError {
+    let kind = match rest.chars().next() {
+        Some(found) => ErrorKind::UnexpectedToken { found },
+        None => ErrorKind::UnexpectedEof,
+    };
+    SyntaxError {
+        remaining: rest.len(),
+        message: message.into(),
+        show_offset: false,
+        kind,
+        path: Vec::new(),
+    }
+}
 
-    if let Some(rest) = input.strip_prefix("false") {
-        return (Value::Boolean(false), rest);
+impl SyntaxError {
+    pub(crate) fn kind(self, kind: ErrorKind) -> Self {
+        SyntaxError { kind, ..self }
     }
-    if let Some(rest) = input.strip_prefix("null") {
-        return (Value::Null, rest);
+}
+
+/// Like [`error`], for input that ends in the middle of the token at `rest`.
+pub(crate) fn truncated(rest: &str, message: impl Into<String>) -> SyntaxError {
+    error(rest, message).kind(ErrorKind::UnexpectedEof)
+}
+
+/// Converts a [`SyntaxError`] raised while parsing `input` to a [`ParseError`].
+pub(crate) fn locate(input: &str, e: SyntaxError) -> ParseError {
+    let offset = input.len() - e.remaining;
+    // Whatever was expected, the input ended before it.
+    let kind = if e.remaining == 0 {
+        ErrorKind::UnexpectedEof
+    } else {
+        e.kind
+    };
+    let mut err = if e.show_offset {
+        let message = format!("{} at offset {offset}", e.message);
+        ParseError::new(input, offset, kind, message)
+    } else {
+        ParseError::new(input, offset, kind, e.message)
+    };
+    err.path = e.path;
+    err.path.reverse();
+    err
+}
+
+/// Options for non-default parsing behavior.
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Keep every number as [`Value::RawNumber`] holding its source text, so
+    /// that numbers `f64` cannot represent survive re-serialization unchanged.
+    pub raw_numbers: bool,
+    /// Also accept `'...'` strings, as written in JavaScript. Inside them a
+    /// `"` is an ordinary character and `\'` escapes a single quote.
+    pub allow_single_quotes: bool,
+    /// Also accept object keys written as bare identifiers, e.g. `{name: 1}`.
+    /// An identifier matches `[A-Za-z_$][A-Za-z0-9_$]*`.
+    pub allow_unquoted_keys: bool,
+    /// Reject numbers too large for `f64`, such as `1e400`, instead of
+    /// reading them as infinity.
+    pub error_on_number_overflow: bool,
+    /// Treat `//` comments running to the end of the line as whitespace.
+    pub allow_line_comments: bool,
+    /// Tab stop interval for the column of errors, so that it matches the
+    /// cursor position in an editor, e.g. `8`. By default a tab counts as
+    /// one column like any other character.
+    pub tab_width: usize,
+    /// Reject documents whose root is not one of these kinds, e.g. only
+    /// objects for an API that expects them, before parsing the rest.
+    pub allowed_root_types: Option<Vec<ValueKind>>,
+    /// How deeply arrays and objects may nest, 128 by default. Parsing is
+    /// recursive, so deeper input could overflow the stack.
+    pub max_depth: usize,
+    /// Reject objects that repeat a key. By default the last value wins.
+    pub reject_duplicate_keys: bool,
+    /// Store each distinct object key once per document and share it between
+    /// all objects using it, saving memory on arrays of records with the same
+    /// keys at the cost of a lookup per key.
+    pub intern_keys: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            raw_numbers: false,
+            allow_single_quotes: false,
+            allow_unquoted_keys: false,
+            error_on_number_overflow: false,
+            allow_line_comments: false,
+            tab_width: 0,
+            allowed_root_types: None,
+            max_depth: 128,
+            reject_duplicate_keys: false,
+            intern_keys: false,
+        }
     }
-    if let Some(rest) = input.strip_prefix("true") {
-        return (Value::Boolean(true), rest);
+}
+
+pub(crate) fn parse(input: &str) -> Value {
+    try_parse(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub(crate) fn try_parse(input: &str) -> Result<Value, ParseError> {
+    try_parse_with(input, &ParseOptions::default())
+}
+
+pub(crate) fn try_parse_with(input: &str, opts: &ParseOptions) -> Result<Value, ParseError> {
+    Parser::with_options(opts.clone()).parse(input)
+}
+
+pub(crate) fn parse_borrowed(input: &str) -> Result<BorrowedValue<'_>, ParseError> {
+    Parser::with_options(ParseOptions::default()).parse_borrowed(input)
+}
+
+pub(crate) fn parse_bytes(input: &[u8]) -> Result<Value, ParseError> {
+    let text = std::str::from_utf8(input).map_err(|e| {
+        let offset = e.valid_up_to();
+        // Everything before `offset` is valid, so this cannot fail.
+        let valid = std::str::from_utf8(&input[..offset]).unwrap_or_default();
+        ParseError::new(
+            valid,
+            offset,
+            ErrorKind::InvalidUnicode,
+            format!(
+                "Invalid UTF-8 byte 0x{:02X} at offset {offset}",
+                input[offset]
+            ),
+        )
+    })?;
+    try_parse(text)
+}
+
+pub(crate) fn parse_spanned(input: &str) -> Result<Spanned<Value>, ParseError> {
+    let mut parser = Parser {
+        spans: Some(HashMap::new()),
+        ..Parser::default()
+    };
+    let value = parser.parse(input)?;
+    let spans: HashMap<String, Range<usize>> = parser
+        .spans
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(ptr, (start, end))| (ptr, input.len() - start..input.len() - end))
+        .collect();
+    Ok(Spanned::new(value, spans))
+}
+
+/// A problem in valid input, such as a number that cannot be represented
+/// exactly, reported by [`parse_with_warnings`](crate::parse_with_warnings).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    /// JSON Pointer to the value concerned.
+    pub path: String,
+    /// Byte offset of the value in the input.
+    pub offset: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at \"{}\": {}", self.path, self.message)
     }
-    if input.starts_with('{') {
-        let v = object(input);
-        return (Value::Object(v.0), v.1);
+}
+
+pub(crate) fn parse_with_warnings(input: &str) -> (Result<Value, ParseError>, Vec<Warning>) {
+    let mut parser = Parser {
+        warnings: Some(Vec::new()),
+        ..Parser::default()
+    };
+    let result = parser.parse(input);
+    let warnings = parser.warnings.unwrap_or_default();
+    let warnings = warnings
+        .into_iter()
+        .map(|w| Warning {
+            offset: input.len() - w.offset,
+            ..w
+        })
+        .collect();
+    (result, warnings)
+}
+
+pub(crate) fn parse_partial(input: &str) -> (Result<Value, ParseError>, usize) {
+    let mut parser = Parser::new();
+    let start = skip_trivia(strip_bom(input), &parser.options);
+    let result = match value(start, &mut parser) {
+        // More digits may follow, e.g. `12` of `123`.
+        Ok((Value::Number(_), "")) => Err(truncated(start, "Number may continue.")),
+        result => result,
+    };
+    match result {
+        Ok((v, rest)) => (Ok(v), input.len() - rest.len()),
+        Err(e) => (Err(locate(input, e)), 0),
     }
-    if input.starts_with('[') {
-        let v = array(input);
-        return (Value::Array(v.0), v.1);
+}
+
+pub(crate) fn raw_string(escaped: &str) -> Result<Value, ParseError> {
+    // Offsets into `escaped` are one less than into `quoted`.
+    let quoted = format!("\"{escaped}\"");
+    let position = |e: SyntaxError| {
+        let offset = quoted.len() - e.remaining;
+        ParseError::new(
+            escaped,
+            offset.saturating_sub(1).min(escaped.len()),
+            e.kind,
+            e.message,
+        )
+    };
+    let mut scratch = String::new();
+    let (s, rest) = match string(&quoted, &ParseOptions::default(), &mut scratch) {
+        // Only a trailing backslash escaping the closing quote gets this far.
+        Err(e) if e.remaining == 0 => {
+            return Err(ParseError::new(
+                escaped,
+                escaped.len() - 1,
+                ErrorKind::UnexpectedEof,
+                "Invalid escape sequence: '\\' at end of string.",
+            ));
+        }
+        result => result.map_err(position)?,
+    };
+    if !rest.is_empty() {
+        // Point at the quote that ended the string early.
+        let quote = &quoted[quoted.len() - rest.len() - 1..];
+        return Err(position(error(quote, "Unescaped '\"' in string.")));
     }
-    if input.starts_with('"') {
-        let v = string(input);
-        return (Value::String(v.0), v.1);
+    Ok(Value::String(s.into_owned()))
+}
+
+pub(crate) fn parse_many(input: &str) -> Result<Vec<Value>, ParseError> {
+    ValueStream::new(input).collect()
+}
+
+/// Parses a sequence of JSON values separated by optional whitespace one at
+/// a time, so that a long stream can be processed without holding all of
+/// its values. After the first error the iterator ends.
+///
+/// ```
+/// let mut stream = rj::ValueStream::new("{\"id\": 1} {\"id\": 2}");
+/// assert_eq!(stream.next().unwrap().unwrap()["id"], rj::Value::Number(1.0));
+/// assert_eq!(stream.offset(), 9);
+/// ```
+#[derive(Debug)]
+pub struct ValueStream<'a> {
+    input: &'a str,
+    rest: &'a str,
+    parser: Parser,
+    failed: bool,
+}
+
+impl<'a> ValueStream<'a> {
+    pub fn new(input: &'a str) -> Self {
+        ValueStream {
+            input,
+            rest: strip_bom(input),
+            parser: Parser::new(),
+            failed: false,
+        }
     }
-    if input.starts_with('-') || input.chars().next().is_some_and(|c| c.is_ascii_digit()) {
-        let v = number(input);
-        return (Value::Number(v.0), v.1);
+
+    /// Byte offset just past the last value returned.
+    pub fn offset(&self) -> usize {
+        self.input.len() - self.rest.len()
     }
+}
+
+impl Iterator for ValueStream<'_> {
+    type Item = Result<Value, ParseError>;
 
-    panic!("Unexpected token: '{}'", input);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let rest = skip_trivia(self.rest, &self.parser.options);
+        if rest.is_empty() {
+            self.rest = rest;
+            return None;
+        }
+        match value(rest, &mut self.parser) {
+            Ok((v, rest)) => {
+                self.rest = rest;
+                Some(Ok(v))
+            }
+            Err(e) => {
+                self.failed = true;
+                Some(Err(locate(self.input, e)))
+            }
+        }
+    }
 }
 
-/// whitespace = \x20 \x09 \x0a \x0d
-/// - \x20 space
-/// - \x09 horizontal tab
-/// - \x0a line feed or new line
-/// - \x0d carriage return
-fn is_whitespace(c: char) -> bool {
-    c == '\x20' || c == '\x09' || c == '\x0a' || c == '\x0d'
+/// A parser that keeps its scratch buffers between calls, so that parsing
+/// many small documents, e.g. messages on a server, does not regrow them
+/// for every string with escapes.
+///
+/// ```
+/// let mut parser = rj::Parser::new();
+/// for msg in [r#"{"id": 1}"#, r#"{"id": 2}"#] {
+///     assert!(parser.parse(msg).unwrap().contains_key("id"));
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Parser {
+    options: ParseOptions,
+    /// Decoding buffer for strings with escapes, cleared before each use.
+    scratch: String,
+    /// When set, collects the remaining input lengths at the start and end
+    /// of each value, keyed by JSON Pointer.
+    spans: Option<HashMap<String, (usize, usize)>>,
+    /// When set, collects warnings, positioned by the remaining input length
+    /// until parsing ends.
+    warnings: Option<Vec<Warning>>,
+    /// Pointer to the value being parsed while `spans` or `warnings` is set.
+    path: String,
+    /// Number of arrays and objects around the value being parsed.
+    depth: usize,
+    /// The keys of the document so far, with `intern_keys`.
+    keys: HashSet<Arc<str>>,
 }
 
-fn eat_whitespace(input: &str) -> &str {
-    let mut pos = 0;
-    for c in input.chars() {
-        if !is_whitespace(c) {
-            break;
+impl Parser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(options: ParseOptions) -> Self {
+        Self {
+            options,
+            ..Self::default()
         }
-        pos += c.len_utf8(); // Advance by byte length of char
     }
-    &input[pos..]
-}
 
-fn object(input: &str) -> (HashMap<String, Value>, &str) {
-    let mut cur_input = eat_whitespace(input)
-        .strip_prefix('{')
-        .expect("object must start with '{'");
+    /// Parses one JSON document, like [`try_parse_with`](crate::try_parse_with).
+    pub fn parse(&mut self, input: &str) -> Result<Value, ParseError> {
+        self.parse_tree(input)
+    }
 
-    if let Some(rest) = eat_whitespace(cur_input).strip_prefix('}') {
-        return (HashMap::new(), rest);
+    /// Parses one JSON document into a [`BorrowedValue`], like
+    /// [`parse_borrowed`](crate::parse_borrowed).
+    pub fn parse_borrowed<'a>(&mut self, input: &'a str) -> Result<BorrowedValue<'a>, ParseError> {
+        self.parse_tree(input)
     }
 
-    let mut obj: HashMap<String, Value> = HashMap::new();
-    loop {
-        // Parse key
-        let (key, rest) = string(eat_whitespace(cur_input));
-        cur_input = eat_whitespace(rest)
-            .strip_prefix(':')
-            .expect("Expected ':' after object key.");
+    fn parse_tree<'a, T: Tree<'a>>(&mut self, input: &'a str) -> Result<T, ParseError> {
+        // Keys are only shared within a document, so that a long-lived parser
+        // does not accumulate every key it has seen.
+        self.keys.clear();
+        let tab_width = self.options.tab_width;
+        let position = |e| {
+            let mut err = locate(input, e);
+            err.expand_tabs(input, tab_width);
+            err
+        };
 
-        // Parse value
-        let (val, rest) = value(cur_input);
-        obj.insert(key, val);
+        let input_start = skip_trivia(strip_bom(input), &self.options);
+        if let Some(allowed) = &self.options.allowed_root_types {
+            check_root(input_start, allowed, &self.options).map_err(position)?;
+        }
+        let (v, rest) = value(input_start, self).map_err(position)?;
+        // After parsing the top-level value, there should ideally be only whitespace left.
+        let rest = skip_trivia(rest, &self.options);
+        if !rest.is_empty() {
+            let message = format!("Unexpected characters after JSON value: '{}'", rest);
+            return Err(position(
+                error(rest, message).kind(ErrorKind::TrailingContent),
+            ));
+        }
+        Ok(v)
+    }
 
-        if let Some(rest) = eat_whitespace(rest).strip_prefix(',') {
-            cur_input = rest;
-        } else if let Some(rest) = eat_whitespace(rest).strip_prefix('}') {
-            cur_input = rest;
-            break;
-        } else {
-            panic!("Expected ',' or '}}' after object value.");
+    /// The stored copy of `key`, added if this is its first occurrence.
+    fn intern(&mut self, key: &str) -> Arc<str> {
+        if let Some(key) = self.keys.get(key) {
+            return key.clone();
         }
+        let key: Arc<str> = Arc::from(key);
+        self.keys.insert(key.clone());
+        key
     }
+}
+
+/// Fails if the value starting `input` is not of an `allowed` kind, judging
+/// by its first character. Input that starts no value is left to the parser.
+fn check_root(input: &str, allowed: &[ValueKind], opts: &ParseOptions) -> Result<(), SyntaxError> {
+    let kind = match input.chars().next() {
+        Some('{') => ValueKind::Object,
+        Some('[') => ValueKind::Array,
+        Some('"') => ValueKind::String,
+        Some('\'') if opts.allow_single_quotes => ValueKind::String,
+        Some('-' | '0'..='9') => ValueKind::Number,
+        Some('t' | 'f') => ValueKind::Boolean,
+        Some('n') => ValueKind::Null,
+        _ => return Ok(()),
+    };
+    if allowed.contains(&kind) {
+        return Ok(());
+    }
+    let names: Vec<&str> = allowed.iter().map(|k| k.name()).collect();
+    Err(error(
+        input,
+        format!(
+            "Expected {} as the root value, found {}",
+            names.join(" or "),
+            kind.name()
+        ),
+    ))
+}
+
+/// Skips a leading UTF-8 byte order mark, which RFC 8259 allows parsers to
+/// ignore. Files saved by some Windows tools start with one.
+fn strip_bom(input: &str) -> &str {
+    input.strip_prefix('\u{FEFF}').unwrap_or(input)
+}
+
+/// What the parser builds from the tokens it reads: a [`Value`], or a
+/// [`BorrowedValue`] that keeps borrowing from the input.
+pub(crate) trait Tree<'a>: Sized {
+    /// The members of an object while it is parsed.
+    type Members: Default;
 
-    (obj, cur_input)
+    fn null() -> Self;
+    fn boolean(b: bool) -> Self;
+    fn number(n: f64) -> Self;
+    /// A number with [`ParseOptions::raw_numbers`].
+    fn raw_number(text: &'a str) -> Self;
+    fn string(s: Cow<'a, str>) -> Self;
+    fn array(elements: Vec<Self>) -> Self;
+    fn object(members: Self::Members) -> Self;
+    fn contains_key(members: &Self::Members, key: &str) -> bool;
+    fn insert(members: &mut Self::Members, key: Cow<'a, str>, value: Self, p: &mut Parser);
 }
 
-fn array(input: &str) -> (Vec<Value>, &str) {
-    let mut cur_input = eat_whitespace(input)
-        .strip_prefix('[')
-        .expect("array must start with '['");
+impl<'a> Tree<'a> for Value {
+    type Members = Map;
 
-    if let Some(rest) = eat_whitespace(cur_input).strip_prefix(']') {
-        return (Vec::new(), rest);
+    fn null() -> Self {
+        Value::Null
     }
 
-    let mut values: Vec<Value> = Vec::new();
-    let (v, rest) = value(cur_input);
-    values.push(v);
-    cur_input = rest;
+    fn boolean(b: bool) -> Self {
+        Value::Boolean(b)
+    }
 
-    while let Some(rest) = eat_whitespace(cur_input).strip_prefix(',') {
-        let (v, rest) = value(rest);
-        values.push(v);
-        cur_input = rest;
+    fn number(n: f64) -> Self {
+        Value::Number(n)
     }
 
-    cur_input = eat_whitespace(cur_input)
-        .strip_prefix(']')
-        .expect("array must end with ']'");
+    fn raw_number(text: &'a str) -> Self {
+        Value::RawNumber(text.to_string())
+    }
+
+    fn string(s: Cow<'a, str>) -> Self {
+        Value::String(s.into_owned())
+    }
 
-    (values, cur_input)
+    fn array(elements: Vec<Self>) -> Self {
+        Value::Array(elements)
+    }
+
+    fn object(members: Map) -> Self {
+        Value::Object(members)
+    }
+
+    fn contains_key(members: &Map, key: &str) -> bool {
+        members.contains_key(key)
+    }
+
+    fn insert(members: &mut Map, key: Cow<'a, str>, value: Self, p: &mut Parser) {
+        let key = if p.options.intern_keys {
+            p.intern(&key)
+        } else {
+            Arc::from(key)
+        };
+        members.insert(key, value);
+    }
 }
 
-fn string(input: &str) -> (String, &str) {
-    let cur_input = eat_whitespace(input)
-        .strip_prefix('"')
-        .expect("object must start with '\"'");
+fn value<'a, T: Tree<'a>>(input: &'a str, p: &mut Parser) -> Result<(T, &'a str), SyntaxError> {
+    let input = skip_trivia(input, &p.options);
+    let (v, rest) = bare_value(input, p)?;
+    if let Some(spans) = &mut p.spans {
+        spans.insert(p.path.clone(), (input.len(), rest.len()));
+    }
+    Ok((v, rest))
+}
 
-    if let Some(rest) = eat_whitespace(cur_input).strip_prefix('"') {
-        return (String::new(), rest);
+/// Parses the value of an object member or array element, extending the
+/// pointer path by `segment` while spans or warnings are recorded, and adding
+/// it to the path of any error.
+fn child<'a, T: Tree<'a>>(
+    input: &'a str,
+    p: &mut Parser,
+    segment: impl FnOnce() -> PathSegment,
+) -> Result<(T, &'a str), SyntaxError> {
+    if p.spans.is_none() && p.warnings.is_none() {
+        return value(input, p).map_err(|mut e| {
+            e.path.push(segment());
+            e
+        });
     }
+    let len = p.path.len();
+    let segment = segment();
+    p.path.push_str(&segment.to_string());
+    let result = value(input, p);
+    p.path.truncate(len);
+    result.map_err(|mut e| {
+        e.path.push(segment);
+        e
+    })
+}
 
-    let mut chars = input.char_indices(); // Iterator that yields (byte_index, char)
-    let mut parsed_string = String::new();
+/// Integers up to this magnitude, 2^53 - 1, convert to `f64` exactly, and so
+/// do all integers between them.
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
 
-    // 1. Expect the opening double quote
-    let Some((start_quote_idx, c)) = chars.next() else {
-        panic!("String must start with '\"'. Input was empty.");
+/// Parses a value starting right at the beginning of `input`.
+fn bare_value<'a, T: Tree<'a>>(
+    input: &'a str,
+    p: &mut Parser,
+) -> Result<(T, &'a str), SyntaxError> {
+    let (token, rest) = token(input, &p.options, &mut p.scratch)?;
+    let value = match token {
+        Token::BeginObject | Token::BeginArray => {
+            let limit = p.options.max_depth;
+            if p.depth == limit {
+                let message = format!("Nesting deeper than {limit} levels");
+                return Err(error(input, message).kind(ErrorKind::DepthExceeded { limit }));
+            }
+            p.depth += 1;
+            let result = if token == Token::BeginObject {
+                object::<T>(rest, p).map(|(obj, rest)| (T::object(obj), rest))
+            } else {
+                array(rest, p).map(|(arr, rest)| (T::array(arr), rest))
+            };
+            p.depth -= 1;
+            return result;
+        }
+        Token::String(s) => T::string(s),
+        Token::Number(text) => number(input, text, p)?,
+        Token::True => T::boolean(true),
+        Token::False => T::boolean(false),
+        Token::Null => T::null(),
+        Token::EndObject | Token::EndArray | Token::NameSeparator | Token::ValueSeparator => {
+            return Err(error(input, format!("Unexpected token: '{}'", &input[..1])));
+        }
     };
-    if c != '"' {
-        panic!(
-            "String must start with '\"'. Found '{}' at index {}.",
-            c, start_quote_idx
-        );
+    Ok((value, rest))
+}
+
+/// Converts the number `text` found at the start of `input`.
+fn number<'a, T: Tree<'a>>(input: &str, text: &'a str, p: &mut Parser) -> Result<T, SyntaxError> {
+    let invalid = |message| error(input, message).kind(ErrorKind::InvalidNumber);
+    // The lexer only admits text that `f64` parsing accepts.
+    let n: f64 = text
+        .parse()
+        .map_err(|_| invalid(format!("Invalid number: '{}'", text)))?;
+    if p.options.error_on_number_overflow && n.is_infinite() {
+        return Err(invalid(format!("Number out of range: '{}'", text)));
+    }
+    if p.options.raw_numbers {
+        return Ok(T::raw_number(text));
     }
+    if let Some(warnings) = &mut p.warnings {
+        if n.abs() > MAX_SAFE_INTEGER && !text.contains(['.', 'e', 'E']) {
+            warnings.push(Warning {
+                path: p.path.clone(),
+                offset: input.len(),
+                message: format!(
+                    "integer {text} exceeds the safe integer range, precision may be lost"
+                ),
+            });
+        }
+    }
+    Ok(T::number(n))
+}
 
+/// Parses the members of an object after its `{`.
+fn object<'a, T: Tree<'a>>(
+    input: &'a str,
+    p: &mut Parser,
+) -> Result<(T::Members, &'a str), SyntaxError> {
+    if let Some(rest) = skip_trivia(input, &p.options).strip_prefix('}') {
+        return Ok((T::Members::default(), rest));
+    }
+    let mut cur_input = input;
+
+    let mut obj = T::Members::default();
     loop {
-        let Some((idx, c)) = chars.next() else {
-            panic!("Unterminated string: missing closing '\"'.");
+        // Parse key
+        let key_input = skip_trivia(cur_input, &p.options);
+        let (key, rest) = match identifier(key_input) {
+            Some((key, rest)) if p.options.allow_unquoted_keys => (Cow::Borrowed(key), rest),
+            _ => string(key_input, &p.options, &mut p.scratch)?,
         };
-        // `current_byte_pos` tracks the byte index *after* the character just processed.
-        // It starts after the opening quote.
-        let current_byte_pos = idx + c.len_utf8(); // Update position to *after* the current char
+        let rest = skip_trivia(rest, &p.options);
+        cur_input = rest
+            .strip_prefix(':')
+            .ok_or_else(|| error(rest, "Expected ':' after object key."))?;
 
-        match c {
-            '"' => {
-                return (parsed_string, &input[current_byte_pos..]);
-            }
-            '\\' => {
-                // Handle escape sequence
-                let Some((_, escaped_char)) = chars.next() else {
-                    panic!("Invalid escape sequence: '\\' at end of string.");
-                };
-
-                match escaped_char {
-                    '"' => parsed_string.push('"'),    // quotation mark
-                    '\\' => parsed_string.push('\\'),  // reverse solidus
-                    '/' => parsed_string.push('/'),    // solidus
-                    'b' => parsed_string.push('\x08'), // backspace
-                    'f' => parsed_string.push('\x0C'), // form feed
-                    'n' => parsed_string.push('\n'),   // line feed
-                    'r' => parsed_string.push('\r'),   // carriage return
-                    't' => parsed_string.push('\t'),   // tab
-                    'u' => {
-                        // uXXXX
-                        let mut hex_val: u32 = 0;
-                        for _ in 0..4 {
-                            match chars.next() {
-                                Some((_, '"')) => {
-                                    panic!(
-                                        "Invalid unicode escape sequence: expected 4 hex digits after '\\u'."
-                                    );
-                                }
-                                Some((_, c)) => {
-                                    let digit = c
-                                        .to_digit(16)
-                                        .expect("Invalid hex digit in unicode escape.");
-                                    hex_val = (hex_val << 4) | digit;
-                                }
-                                None => {
-                                    panic!(
-                                        "Invalid unicode escape sequence: expected 4 hex digits after '\\u'."
-                                    );
-                                }
-                            }
-                        }
-
-                        let unicode_char =
-                            char::from_u32(hex_val).expect("Invalid unicode scalar value.");
-                        parsed_string.push(unicode_char);
-                    }
-                    _ => panic!("Invalid escape sequence: '\\{}'", escaped_char),
-                }
-            }
-            // JSON strings cannot contain unescaped control characters like newlines or carriage returns
-            _ if c == '\n' || c == '\r' || c == '\t' => {
-                // \t is allowed escaped, but not unescaped
-                panic!("Unescaped control character in string: '{}'", c);
-            }
-            _ => {
-                // Regular character
-                parsed_string.push(c);
-            }
+        if p.options.reject_duplicate_keys && T::contains_key(&obj, &key) {
+            let message = format!("Duplicate key \"{key}\"");
+            let key = key.into_owned();
+            return Err(error(key_input, message).kind(ErrorKind::DuplicateKey { key }));
+        }
+
+        // Parse value
+        let (val, rest) = child(cur_input, p, || PathSegment::Key(key.to_string()))?;
+        T::insert(&mut obj, key, val, p);
+
+        let rest = skip_trivia(rest, &p.options);
+        if let Some(rest) = rest.strip_prefix(',') {
+            cur_input = rest;
+        } else if let Some(rest) = rest.strip_prefix('}') {
+            cur_input = rest;
+            break;
+        } else {
+            return Err(error(rest, "Expected ',' or '}' after object value."));
         }
     }
+
+    Ok((obj, cur_input))
 }
 
-fn number(input: &str) -> (f64, &str) {
-    // ignore whitespace first
-    let mut cur_input = eat_whitespace(input);
+/// Parses the elements of an array after its `[`.
+fn array<'a, T: Tree<'a>>(
+    input: &'a str,
+    p: &mut Parser,
+) -> Result<(Vec<T>, &'a str), SyntaxError> {
+    if let Some(rest) = skip_trivia(input, &p.options).strip_prefix(']') {
+        return Ok((Vec::new(), rest));
+    }
+    let mut cur_input = input;
+
+    let mut values = Vec::new();
+    let (v, rest) = child(cur_input, p, || PathSegment::Index(0))?;
+    values.push(v);
+    cur_input = rest;
 
-    let mut minus = false;
-    if let Some(rest) = cur_input.strip_prefix('-') {
-        minus = true;
+    while let Some(rest) = skip_trivia(cur_input, &p.options).strip_prefix(',') {
+        let (v, rest) = child(rest, p, || PathSegment::Index(values.len()))?;
+        values.push(v);
         cur_input = rest;
     }
 
-    let mut buf = String::new();
-    let mut enable_sign = false;
-    for c in cur_input.chars() {
-        match c {
-            '0'..='9' => buf.push(c),
-            '.' => buf.push(c),
-            'e' | 'E' => {
-                enable_sign = true;
-                buf.push(c);
-            }
-            '-' | '+' => {
-                if enable_sign {
-                    buf.push(c);
-                    enable_sign = false;
-                } else {
-                    panic!(
-                        "sign only allowed at the beginning of the number or immediately after 'e' or 'E' for exponents"
-                    );
-                }
-            }
-            _ => break, // the char is not part of number.
-        }
-    }
+    let rest = skip_trivia(cur_input, &p.options);
+    cur_input = rest
+        .strip_prefix(']')
+        .ok_or_else(|| error(rest, "array must end with ']'"))?;
 
-    cur_input = cur_input.strip_prefix(&buf).unwrap();
-    if minus {
-        (buf.parse::<f64>().unwrap() * -1.0, cur_input)
-    } else {
-        (buf.parse().unwrap(), cur_input)
-    }
+    Ok((values, cur_input))
 }
 
 #[cfg(test)]
@@ -508,7 +923,7 @@ mod tests {
         match parsed {
             Value::Array(arr) => assert_eq!(
                 arr,
-                vec![Value::Object(HashMap::from([(
+                vec![Value::Object(Map::from([(
                     "key1".to_string(),
                     Value::Boolean(true)
                 )]))]
@@ -525,8 +940,8 @@ mod tests {
             Value::Array(arr) => assert_eq!(
                 arr,
                 vec![
-                    Value::Object(HashMap::from([("key1".to_string(), Value::Boolean(true))])),
-                    Value::Object(HashMap::from([("key1".to_string(), Value::Boolean(true))])),
+                    Value::Object(Map::from([("key1".to_string(), Value::Boolean(true))])),
+                    Value::Object(Map::from([("key1".to_string(), Value::Boolean(true))])),
                 ]
             ),
             _ => panic!("Expected an array, got {:?}", parsed),
@@ -646,4 +1061,650 @@ mod tests {
         assert_eq!(v[1]["Zip"], r#""94085""#.into());
         assert_eq!(v[1]["Country"], r#""US""#.into());
     }
+
+    #[test]
+    fn try_parse_reports_position() {
+        let err = try_parse("{\n  \"a\": x\n}").unwrap_err();
+        assert_eq!(err.message(), "Unexpected token: 'x'");
+        assert_eq!(err.offset(), 9);
+        assert_eq!(err.line(), 2);
+        assert_eq!(err.column(), 8);
+    }
+
+    #[test]
+    fn raw_string_decodes_escapes() {
+        assert_eq!(raw_string("a\\n"), Ok(Value::String("a\n".to_string())));
+        assert_eq!(
+            raw_string(r"\u00e9\ud83d\ude00 \/"),
+            Ok(Value::String("é😀 /".to_string()))
+        );
+        assert_eq!(raw_string(""), Ok(Value::String(String::new())));
+
+        let err = raw_string(r#"a"b"#).unwrap_err();
+        assert_eq!(
+            (err.message(), err.offset()),
+            ("Unescaped '\"' in string.", 1)
+        );
+        let err = raw_string(r"ab\x").unwrap_err();
+        assert_eq!(
+            (err.message(), err.offset()),
+            ("Invalid escape sequence: '\\x'", 2)
+        );
+        assert_eq!(raw_string("a\\").unwrap_err().offset(), 1);
+    }
+
+    #[test]
+    fn allowed_root_types() {
+        let objects_only = ParseOptions {
+            allowed_root_types: Some(vec![ValueKind::Object]),
+            ..ParseOptions::default()
+        };
+        let err = try_parse_with(" []", &objects_only).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "Expected object as the root value, found array"
+        );
+        assert_eq!(err.offset(), 1);
+        assert!(try_parse_with(r#"{"a": [1]}"#, &objects_only).is_ok());
+
+        let err = try_parse_with("[1, 2", &objects_only).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "Expected object as the root value, found array"
+        );
+        let err = try_parse_with("x", &objects_only).unwrap_err();
+        assert_eq!(err.message(), "Unexpected token: 'x'");
+
+        let containers = ParseOptions {
+            allowed_root_types: Some(vec![ValueKind::Object, ValueKind::Array]),
+            ..ParseOptions::default()
+        };
+        assert!(try_parse_with("[]", &containers).is_ok());
+        for scalar in ["\"a\"", "-1", "true", "null"] {
+            let err = try_parse_with(scalar, &containers).unwrap_err();
+            let kind = parse(scalar).type_name();
+            assert_eq!(
+                err.message(),
+                format!("Expected object or array as the root value, found {kind}")
+            );
+        }
+    }
+
+    #[test]
+    fn tab_width() {
+        let input = "{\n\t\"a\": x\n}";
+        assert_eq!(try_parse(input).unwrap_err().column(), 7);
+        for (tab_width, column) in [(0, 7), (1, 7), (4, 10), (8, 14)] {
+            let opts = ParseOptions {
+                tab_width,
+                ..ParseOptions::default()
+            };
+            let err = try_parse_with(input, &opts).unwrap_err();
+            assert_eq!((err.line(), err.column()), (2, column), "{tab_width}");
+        }
+
+        // A tab after other text only advances to the next tab stop.
+        let opts = ParseOptions {
+            tab_width: 4,
+            ..ParseOptions::default()
+        };
+        let err = try_parse_with("[1,\t2,\tx]", &opts).unwrap_err();
+        assert_eq!(err.column(), 9);
+    }
+
+    #[test]
+    fn error_kinds() {
+        let err = try_parse(r#"{"a" 1}"#).unwrap_err();
+        assert_eq!(err.kind(), &ErrorKind::UnexpectedToken { found: '1' });
+        assert_eq!(err.offset(), 5);
+
+        let cases = [
+            ("[01]", ErrorKind::InvalidNumber),
+            ("[1.x]", ErrorKind::InvalidNumber),
+            (r#""\x""#, ErrorKind::InvalidEscape { escape: 'x' }),
+            (r#""\u12g4""#, ErrorKind::InvalidUnicode),
+            (r#""\udc00""#, ErrorKind::InvalidUnicode),
+            ("[] []", ErrorKind::TrailingContent),
+            ("[1 2]", ErrorKind::UnexpectedToken { found: '2' }),
+            ("[1,", ErrorKind::UnexpectedEof),
+        ];
+        for (json, kind) in cases {
+            assert_eq!(try_parse(json).unwrap_err().kind(), &kind, "{json}");
+        }
+        assert_eq!(
+            parse_bytes(b"[\"\xff\"]").unwrap_err().kind(),
+            &ErrorKind::InvalidUnicode
+        );
+    }
+
+    #[test]
+    fn nesting_depth_limit() {
+        let nested = |depth: usize| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(try_parse(&nested(128)).is_ok());
+        let err = try_parse(&nested(129)).unwrap_err();
+        assert_eq!(err.kind(), &ErrorKind::DepthExceeded { limit: 128 });
+        assert_eq!(err.message(), "Nesting deeper than 128 levels");
+        assert_eq!(err.offset(), 128);
+
+        let opts = ParseOptions {
+            max_depth: 2,
+            ..ParseOptions::default()
+        };
+        assert!(try_parse_with(r#"{"a": [1]}"#, &opts).is_ok());
+        let err = try_parse_with(r#"{"a": [{}]}"#, &opts).unwrap_err();
+        assert_eq!(err.kind(), &ErrorKind::DepthExceeded { limit: 2 });
+        assert_eq!(err.pointer(), "/a/0");
+
+        // The depth is not carried over from a failed document.
+        let mut parser = Parser::with_options(opts);
+        assert!(parser.parse("[[[]]]").is_err());
+        assert!(parser.parse("[[]]").is_ok());
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let json = r#"{"a": 1, "b": {"c": 2, "c": 3}}"#;
+        assert_eq!(parse(json)["b"]["c"], Value::Number(3.0));
+
+        let opts = ParseOptions {
+            reject_duplicate_keys: true,
+            ..ParseOptions::default()
+        };
+        let err = try_parse_with(json, &opts).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &ErrorKind::DuplicateKey {
+                key: "c".to_string()
+            }
+        );
+        assert_eq!(err.message(), "Duplicate key \"c\"");
+        assert_eq!((err.offset(), err.pointer().as_str()), (23, "/b"));
+    }
+
+    #[test]
+    fn empty_input() {
+        for input in ["", "   ", "\n\t", "\u{FEFF}"] {
+            let err = try_parse(input).unwrap_err();
+            assert_eq!(err.kind(), &ErrorKind::UnexpectedEof, "{input:?}");
+            assert_eq!(err.message(), "Unexpected end of input.");
+            assert_eq!(err.offset(), input.len());
+        }
+        assert_eq!(
+            try_parse("[1,").unwrap_err().kind(),
+            &ErrorKind::UnexpectedEof
+        );
+        assert_eq!(
+            try_parse("[1 2]").unwrap_err().kind(),
+            &ErrorKind::UnexpectedToken { found: '2' }
+        );
+    }
+
+    #[test]
+    fn parse_partial_needs_more_data() {
+        let (result, consumed) = parse_partial(r#"{"a":"#);
+        assert_eq!(result.unwrap_err().kind(), &ErrorKind::UnexpectedEof);
+        assert_eq!(consumed, 0);
+
+        for cut in [
+            "{",
+            "{\"a\"",
+            "{\"a\":1",
+            "[1,",
+            "\"ab",
+            "\"a\\",
+            "\"\\u12",
+            "\"\\ud83d",
+            "tru",
+            "-",
+            "1.",
+            "2e+",
+            "12",
+        ] {
+            let (result, _) = parse_partial(cut);
+            assert_eq!(
+                result.unwrap_err().kind(),
+                &ErrorKind::UnexpectedEof,
+                "{cut}"
+            );
+        }
+        for bad in ["{]", "[1 2", "\"\\x", "trux", "-x", "\"\\ud83dx"] {
+            let (result, _) = parse_partial(bad);
+            assert_ne!(
+                result.unwrap_err().kind(),
+                &ErrorKind::UnexpectedEof,
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_partial_reports_consumed_bytes() {
+        let input = " {\"a\": [1]}\n{\"b\"";
+        let (result, consumed) = parse_partial(input);
+        assert_eq!(result, Ok(parse(r#"{"a": [1]}"#)));
+        assert_eq!(consumed, 11);
+
+        let (result, consumed) = parse_partial(&input[consumed..]);
+        assert!(result.is_err());
+        assert_eq!(consumed, 0);
+
+        assert_eq!(parse_partial("12 "), (Ok(Value::Number(12.0)), 2));
+    }
+
+    #[test]
+    fn error_path() {
+        let err = try_parse(r#"{"a":[1,2,{"b": }]}"#).unwrap_err();
+        assert_eq!(
+            err.path(),
+            [
+                PathSegment::Key("a".to_string()),
+                PathSegment::Index(2),
+                PathSegment::Key("b".to_string()),
+            ]
+        );
+        assert_eq!(err.pointer(), "/a/2/b");
+
+        // Errors in the object itself stop at the object.
+        let err = try_parse(r#"[{"x/y": [true false]}]"#).unwrap_err();
+        assert_eq!(err.pointer(), "/0/x~1y");
+        let err = try_parse(r#"{"a": {"b" 1}}"#).unwrap_err();
+        assert_eq!(err.pointer(), "/a");
+        assert_eq!(try_parse("[1,").unwrap_err().pointer(), "/1");
+        assert!(try_parse("x").unwrap_err().path().is_empty());
+
+        let err = parse_spanned(r#"{"a":[1,2,{"b": }]}"#).unwrap_err();
+        assert_eq!(err.pointer(), "/a/2/b");
+    }
+
+    #[test]
+    fn try_parse_does_not_panic() {
+        for json in ["", "x", "[1,", r#"{"a" 1}"#, r#""\u12"#, "1e", "-"] {
+            assert!(try_parse(json).is_err(), "{json} should not parse");
+        }
+    }
+
+    #[test]
+    fn trailing_garbage_after_scalars() {
+        let cases = [
+            (r#""a"x"#, 3, "Unexpected characters after JSON value: 'x'"),
+            ("truex", 4, "Unexpected characters after JSON value: 'x'"),
+            (
+                "truefalse",
+                4,
+                "Unexpected characters after JSON value: 'false'",
+            ),
+            ("false x", 6, "Unexpected characters after JSON value: 'x'"),
+            ("null,", 4, "Unexpected characters after JSON value: ','"),
+            ("123abc", 3, "Unexpected character 'a' after number."),
+            ("1true", 1, "Unexpected character 't' after number."),
+            ("-1 2", 3, "Unexpected characters after JSON value: '2'"),
+        ];
+        for (json, offset, message) in cases {
+            let err = try_parse(json).unwrap_err();
+            assert_eq!((err.offset(), err.message()), (offset, message), "{json}");
+        }
+    }
+
+    #[test]
+    fn dangling_exponent() {
+        let err = try_parse("1e").unwrap_err();
+        assert_eq!(err.offset(), 0);
+        assert_eq!(err.message(), "Invalid number: '1e'");
+    }
+
+    #[test]
+    fn numbers_next_to_structural_characters() {
+        for (json, expected) in [
+            ("[1]", "[1]"),
+            ("[1,2]", "[1,2]"),
+            ("[[1],[-2]]", "[[1],[-2]]"),
+            ("[0 ,1e2\t]", "[0,100]"),
+            (r#"{"a":1}"#, r#"{"a":1}"#),
+            (r#"{"a":-1.5e3,"b":0}"#, r#"{"a":-1500,"b":0}"#),
+        ] {
+            assert_eq!(parse(json).to_compact_string(), expected, "{json}");
+        }
+
+        let cases = [
+            ("[1 2]", 3, "array must end with ']'"),
+            ("[1,2 3]", 5, "array must end with ']'"),
+            ("[1:]", 2, "array must end with ']'"),
+            ("[1}", 2, "array must end with ']'"),
+            ("[1x]", 2, "Unexpected character 'x' after number."),
+            ("[1.5e2e]", 6, "Unexpected character 'e' after number."),
+            (
+                r#"{"a":1 "b":2}"#,
+                7,
+                "Expected ',' or '}' after object value.",
+            ),
+            (r#"{"a":1]"#, 6, "Expected ',' or '}' after object value."),
+            (r#"{"a":1x}"#, 6, "Unexpected character 'x' after number."),
+        ];
+        for (json, offset, message) in cases {
+            let err = try_parse(json).unwrap_err();
+            assert_eq!((err.offset(), err.message()), (offset, message), "{json}");
+        }
+    }
+
+    #[test]
+    fn number_scans_exact_prefix() {
+        let cases = [
+            ("1.2.3", 3, "Unexpected characters after JSON value: '.3'"),
+            ("1.2e3.4", 5, "Unexpected characters after JSON value: '.4'"),
+            ("1ee2", 0, "Invalid number: '1e'"),
+            ("1e+", 0, "Invalid number: '1e+'"),
+            ("1.", 0, "Invalid number: '1.'"),
+            ("1.e5", 0, "Invalid number: '1.'"),
+            ("-", 0, "Invalid number: '-'"),
+            ("-.5", 0, "Invalid number: '-'"),
+            ("1-2", 1, "Unexpected characters after JSON value: '-2'"),
+            ("01", 0, "Leading zeros are not allowed in numbers."),
+            ("-007", 1, "Leading zeros are not allowed in numbers."),
+            ("[1.2.3]", 4, "array must end with ']'"),
+        ];
+        for (json, offset, message) in cases {
+            let err = try_parse(json).unwrap_err();
+            assert_eq!((err.offset(), err.message()), (offset, message), "{json}");
+        }
+        for (json, n) in [
+            ("0", 0.0),
+            ("-0.5", -0.5),
+            ("1.2e3", 1200.0),
+            ("10E-1", 1.0),
+        ] {
+            assert_eq!(parse(json), Value::Number(n), "{json}");
+        }
+    }
+
+    #[test]
+    fn unicode_escape_bmp() {
+        assert_eq!(parse(r#""\u00e9\u3042""#), Value::String("éあ".to_string()));
+    }
+
+    #[test]
+    fn unicode_escape_surrogate_pair() {
+        assert_eq!(
+            parse(r#""\ud83d\ude00!""#),
+            Value::String("😀!".to_string())
+        );
+    }
+
+    #[test]
+    fn unicode_escape_high_surrogate_without_low() {
+        for json in [r#""\ud83d""#, r#""\ud83dx""#, r#""\ud83d\u0041""#] {
+            let err = try_parse(json).unwrap_err();
+            assert_eq!(err.message(), "Unpaired high surrogate in unicode escape.");
+            assert_eq!(err.offset(), 1);
+        }
+    }
+
+    #[test]
+    fn unicode_escape_low_surrogate_first() {
+        let err = try_parse(r#""a\ude00\ud83d""#).unwrap_err();
+        assert_eq!(err.message(), "Unexpected low surrogate in unicode escape.");
+        assert_eq!(err.offset(), 2);
+    }
+
+    #[test]
+    fn raw_numbers_round_trip() {
+        let json = r#"{"big":123456789012345678901234567890,"small":[1.0,-2E+3]}"#;
+        let opts = ParseOptions {
+            raw_numbers: true,
+            ..Default::default()
+        };
+        let v = try_parse_with(json, &opts).unwrap();
+        assert_eq!(
+            v["big"],
+            Value::RawNumber("123456789012345678901234567890".to_string())
+        );
+        assert_eq!(v["small"][1], Value::RawNumber("-2E+3".to_string()));
+        let s = v["big"].to_string();
+        assert_eq!(s, "123456789012345678901234567890");
+        assert_eq!(v["small"].to_string(), "[1.0,-2E+3]");
+
+        let lossy = parse(json);
+        assert_ne!(lossy["big"].to_string(), "123456789012345678901234567890");
+    }
+
+    #[test]
+    fn single_quoted_strings() {
+        let opts = ParseOptions {
+            allow_single_quotes: true,
+            ..Default::default()
+        };
+        let v = try_parse_with("{'a':'b'}", &opts).unwrap();
+        assert_eq!(v, Value::from(r#"{"a": "b"}"#));
+
+        let v = try_parse_with(r#"['say "hi"', "it's", 'it\'s']"#, &opts).unwrap();
+        assert_eq!(v, Value::from(r#"["say \"hi\"", "it's", "it's"]"#));
+
+        let err = try_parse_with(r#"'abc""#, &opts).unwrap_err();
+        assert_eq!(err.message(), "Unterminated string: missing closing '''.");
+        assert!(try_parse_with(r#"["it\'s"]"#, &opts).is_err());
+
+        let err = try_parse("{'a':'b'}").unwrap_err();
+        assert_eq!(err.message(), "String must start with '\"'. Found '''.");
+        let err = try_parse("'a'").unwrap_err();
+        assert_eq!(err.message(), "Unexpected token: '''");
+    }
+
+    #[test]
+    fn unquoted_keys() {
+        let opts = ParseOptions {
+            allow_unquoted_keys: true,
+            ..Default::default()
+        };
+        let v = try_parse_with("{foo: 1}", &opts).unwrap();
+        assert_eq!(v, Value::from(r#"{"foo": 1}"#));
+
+        let v = try_parse_with(r#"{ _a1$ : true, "b c": null }"#, &opts).unwrap();
+        assert_eq!(v, Value::from(r#"{"_a1$": true, "b c": null}"#));
+
+        let err = try_parse_with("{1a: 1}", &opts).unwrap_err();
+        assert_eq!(err.message(), "String must start with '\"'. Found '1'.");
+        let err = try_parse_with("{a-b: 1}", &opts).unwrap_err();
+        assert_eq!(err.message(), "Expected ':' after object key.");
+        // Values are still strict: only keys may be unquoted.
+        assert!(try_parse_with("{a: b}", &opts).is_err());
+
+        let err = try_parse("{foo: 1}").unwrap_err();
+        assert_eq!(err.message(), "String must start with '\"'. Found 'f'.");
+    }
+
+    #[test]
+    fn unescaped_control_characters() {
+        let err = try_parse("{\"a\":\"x\ty\"}").unwrap_err();
+        assert_eq!(err.offset(), 7);
+        assert_eq!(
+            err.message(),
+            "unescaped control character U+0009 at offset 7"
+        );
+
+        let err = try_parse("[\"\",\n\"é\n\"]").unwrap_err();
+        assert_eq!(err.offset(), 8);
+        assert_eq!((err.line(), err.column()), (2, 3));
+        assert_eq!(
+            err.message(),
+            "unescaped control character U+000A at offset 8"
+        );
+
+        let err = try_parse("\"\u{1}\"").unwrap_err();
+        assert_eq!(
+            err.message(),
+            "unescaped control character U+0001 at offset 1"
+        );
+        assert_eq!(parse(r#""\t\u0001""#), Value::String("\t\u{1}".to_string()));
+    }
+
+    #[test]
+    fn line_comments() {
+        let opts = ParseOptions {
+            allow_line_comments: true,
+            ..Default::default()
+        };
+        let json = "// config\n{\n  // first\n  \"a\": 1, // one\n  \"b\": [ // list\n    \"//x\"\n  ]\n} // end";
+        let v = try_parse_with(json, &opts).unwrap();
+        assert_eq!(v, Value::from(r#"{"a": 1, "b": ["//x"]}"#));
+
+        let err = try_parse("[1] // end").unwrap_err();
+        assert_eq!(
+            err.message(),
+            "Unexpected characters after JSON value: '// end'"
+        );
+        assert!(try_parse_with("[1 /* no */]", &opts).is_err());
+    }
+
+    #[test]
+    fn leading_byte_order_mark() {
+        assert_eq!(parse("\u{FEFF}{}"), Value::Object(Map::new()));
+        assert_eq!(parse_many("\u{FEFF}1 2").unwrap().len(), 2);
+
+        // Only a leading BOM is skipped; it is not whitespace elsewhere.
+        let err = try_parse("[\u{FEFF}1]").unwrap_err();
+        assert_eq!(err.offset(), 1);
+        assert_eq!(err.message(), "Unexpected token: '\u{feff}'");
+    }
+
+    #[test]
+    fn number_overflow() {
+        assert_eq!(parse("1e400"), Value::Number(f64::INFINITY));
+        assert_eq!(parse("-1e400"), Value::Number(f64::NEG_INFINITY));
+
+        let opts = ParseOptions {
+            error_on_number_overflow: true,
+            ..Default::default()
+        };
+        let err = try_parse_with("[1, 1e400]", &opts).unwrap_err();
+        assert_eq!(err.offset(), 4);
+        assert_eq!(err.message(), "Number out of range: '1e400'");
+        assert!(try_parse_with("-1e400", &opts).is_err());
+        assert_eq!(try_parse_with("1e-400", &opts), Ok(Value::Number(0.0)));
+        assert_eq!(try_parse_with("1e308", &opts), Ok(Value::Number(1e308)));
+    }
+
+    #[test]
+    fn parse_many_values() {
+        let values = parse_many("{\"a\":1}\n{\"a\":2}\n").unwrap();
+        assert_eq!(
+            values,
+            vec![Value::from(r#"{"a":1}"#), Value::from(r#"{"a":2}"#)]
+        );
+        assert_eq!(parse_many(" [] 1\t\"x\"").unwrap().len(), 3);
+        assert_eq!(parse_many("  \n").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn value_stream_yields_one_value_at_a_time() {
+        let input = "{\"a\":1}\n{\"b\":2} {\"c\":3}\n";
+        let mut stream = ValueStream::new(input);
+        let mut offsets = Vec::new();
+        for key in ["a", "b", "c"] {
+            assert!(stream.next().unwrap().unwrap().contains_key(key));
+            offsets.push(stream.offset());
+        }
+        assert_eq!(offsets, [7, 15, 23]);
+        assert!(stream.next().is_none());
+        assert_eq!(stream.offset(), input.len());
+        assert!(stream.next().is_none());
+
+        let mut stream = ValueStream::new("1 x 2");
+        assert_eq!(stream.next(), Some(Ok(Value::Number(1.0))));
+        assert_eq!(stream.next().unwrap().unwrap_err().offset(), 2);
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn precision_warnings() {
+        let (result, warnings) = parse_with_warnings(r#"{"id": 9007199254740993}"#);
+        assert_eq!(result, Ok(parse(r#"{"id": 9007199254740992}"#)));
+        assert_eq!(
+            warnings,
+            [Warning {
+                path: "/id".to_string(),
+                offset: 7,
+                message: "integer 9007199254740993 exceeds the safe integer range, \
+                          precision may be lost"
+                    .to_string(),
+            }]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "at \"/id\": integer 9007199254740993 exceeds the safe integer range, \
+             precision may be lost"
+        );
+
+        let (_, warnings) = parse_with_warnings(
+            "[9007199254740991, -9007199254740991, 1e300, 9007199254740992.5, -9007199254740992]",
+        );
+        let paths: Vec<&str> = warnings.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(paths, ["/4"]);
+
+        let (result, warnings) = parse_with_warnings("[123456789012345678901, x]");
+        assert!(result.is_err());
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn parse_many_reports_error_position() {
+        let err = parse_many("{}\n{\"a\" 1}").unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 6));
+    }
+
+    #[test]
+    fn parser_reuses_scratch_between_calls() {
+        let mut parser = Parser::new();
+        let first = parser
+            .parse(r#"{"long\tkey": "a\nlong escaped value"}"#)
+            .unwrap();
+        assert_eq!(first, parse(r#"{"long\tkey": "a\nlong escaped value"}"#));
+
+        // Leftovers from the previous document must not leak into this one.
+        let second = parser.parse(r#"["x\"y", "plain"]"#).unwrap();
+        assert_eq!(
+            second,
+            Value::Array(vec![
+                Value::String("x\"y".into()),
+                Value::String("plain".into())
+            ])
+        );
+        assert!(parser.parse("[1,]").is_err());
+        assert_eq!(
+            parser.parse(r#""\u00e9""#).unwrap(),
+            Value::String("é".into())
+        );
+    }
+
+    #[test]
+    fn parser_with_options() {
+        let mut parser = Parser::with_options(ParseOptions {
+            raw_numbers: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            parser.parse("1.50").unwrap(),
+            Value::RawNumber("1.50".into())
+        );
+    }
+
+    #[test]
+    fn parse_bytes_reports_invalid_utf8() {
+        assert_eq!(
+            parse_bytes(br#"{"a": "\u00e9"}"#).unwrap(),
+            parse(r#"{"a": "é"}"#)
+        );
+
+        let mut input = br#"["abcdefg"#.to_vec();
+        assert_eq!(input.len(), 9);
+        input.extend_from_slice(b"\n\x80\"]");
+        let err = parse_bytes(&input).unwrap_err();
+        assert_eq!(err.offset(), 10);
+        assert_eq!(err.message(), "Invalid UTF-8 byte 0x80 at offset 10");
+        assert_eq!((err.line(), err.column()), (2, 1));
+
+        // Syntax errors are reported as by `try_parse`.
+        assert_eq!(
+            parse_bytes(b"[1,]").unwrap_err(),
+            try_parse("[1,]").unwrap_err()
+        );
+    }
 }
@@ -0,0 +1,402 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    Map, PointerError, Value,
+    pointer::{array_index, escape_token, split_last, tokens},
+};
+
+/// Error returned when a JSON Patch cannot be applied. `index` is the
+/// position of the failing operation in the patch array.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchError {
+    /// The operation is malformed, e.g. missing `path`, an unknown `op` or a
+    /// pointer with an invalid `~` escape.
+    InvalidOperation { index: usize, reason: String },
+    /// A `path` or `from` pointer does not resolve to a location.
+    PathNotFound { index: usize, path: String },
+    /// A `test` operation found a different value.
+    TestFailed { index: usize, path: String },
+}
+
+impl std::fmt::Display for PatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatchError::InvalidOperation { index, reason } => {
+                write!(f, "invalid operation {index}: {reason}")
+            }
+            PatchError::PathNotFound { index, path } => {
+                write!(f, "operation {index}: path \"{path}\" not found")
+            }
+            PatchError::TestFailed { index, path } => {
+                write!(f, "operation {index}: test failed at \"{path}\"")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+impl Value {
+    /// Applies an RFC 6902 JSON Patch, an array of `add`, `remove`,
+    /// `replace`, `move`, `copy` and `test` operations.
+    ///
+    /// The patch is applied atomically: if any operation fails, `self` is
+    /// left unchanged.
+    pub fn apply_patch(&mut self, ops: &Value) -> Result<(), PatchError> {
+        let Value::Array(ops) = ops else {
+            return Err(PatchError::InvalidOperation {
+                index: 0,
+                reason: format!("expected an array of operations, found {}", ops.type_name()),
+            });
+        };
+        let mut doc = self.clone();
+        for (index, op) in ops.iter().enumerate() {
+            apply_op(&mut doc, op, index)?;
+        }
+        *self = doc;
+        Ok(())
+    }
+
+    /// Computes an RFC 6902 JSON Patch of `add`, `remove` and `replace`
+    /// operations that turns `a` into `b`, so that applying it with
+    /// [`Value::apply_patch`] to `a` yields `b`.
+    pub fn diff(a: &Value, b: &Value) -> Value {
+        let mut ops = Vec::new();
+        diff_into(a, b, String::new(), &mut ops);
+        Value::Array(ops)
+    }
+}
+
+fn diff_into(a: &Value, b: &Value, path: String, ops: &mut Vec<Value>) {
+    if a == b {
+        return;
+    }
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            let keys: BTreeSet<&str> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                let path = format!("{path}/{}", escape_token(key));
+                match (a.get(key), b.get(key)) {
+                    (Some(x), Some(y)) => diff_into(x, y, path, ops),
+                    (Some(_), None) => ops.push(op("remove", path, None)),
+                    (None, Some(y)) => ops.push(op("add", path, Some(y))),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            let common = a.len().min(b.len());
+            for i in 0..common {
+                diff_into(&a[i], &b[i], format!("{path}/{i}"), ops);
+            }
+            // Remove from the back so the remaining indices stay valid.
+            for i in (common..a.len()).rev() {
+                ops.push(op("remove", format!("{path}/{i}"), None));
+            }
+            for (i, v) in b.iter().enumerate().skip(common) {
+                ops.push(op("add", format!("{path}/{i}"), Some(v)));
+            }
+        }
+        _ => ops.push(op("replace", path, Some(b))),
+    }
+}
+
+fn op(name: &str, path: String, value: Option<&Value>) -> Value {
+    let mut obj = Map::from([
+        ("op".to_string(), Value::String(name.to_string())),
+        ("path".to_string(), Value::String(path)),
+    ]);
+    if let Some(v) = value {
+        obj.insert("value".to_string(), v.clone());
+    }
+    Value::Object(obj)
+}
+
+fn apply_op(doc: &mut Value, op: &Value, index: usize) -> Result<(), PatchError> {
+    let invalid = |reason: &str| PatchError::InvalidOperation {
+        index,
+        reason: reason.to_string(),
+    };
+    let member = |name: &str| match op {
+        Value::Object(obj) => obj.get(name),
+        _ => None,
+    };
+    let malformed = |e: PointerError| invalid(&e.to_string());
+    // Malformed pointers are rejected up front, whatever the operation.
+    let pointer_member = |name: &str| match member(name) {
+        Some(Value::String(s)) => tokens(s).map(|_| s.as_str()).map_err(malformed),
+        _ => Err(invalid(&format!("missing \"{name}\" pointer"))),
+    };
+    let value_member = || member("value").ok_or_else(|| invalid("missing \"value\""));
+    let not_found = |path: &str| PatchError::PathNotFound {
+        index,
+        path: path.to_string(),
+    };
+
+    let Some(Value::String(name)) = member("op") else {
+        return Err(invalid("missing \"op\""));
+    };
+    let path = pointer_member("path")?;
+    match name.as_str() {
+        "add" => add(doc, path, value_member()?.clone())
+            .map_err(malformed)?
+            .ok_or_else(|| not_found(path)),
+        "remove" => remove(doc, path)
+            .map_err(malformed)?
+            .map(|_| ())
+            .ok_or_else(|| not_found(path)),
+        "replace" => {
+            let target = doc.pointer_mut(path).ok_or_else(|| not_found(path))?;
+            *target = value_member()?.clone();
+            Ok(())
+        }
+        "move" => {
+            let from = pointer_member("from")?;
+            if path.starts_with(from) && path[from.len()..].starts_with('/') {
+                return Err(invalid("cannot move a value into one of its children"));
+            }
+            let value = remove(doc, from)
+                .map_err(malformed)?
+                .ok_or_else(|| not_found(from))?;
+            add(doc, path, value)
+                .map_err(malformed)?
+                .ok_or_else(|| not_found(path))
+        }
+        "copy" => {
+            let from = pointer_member("from")?;
+            let value = doc.pointer(from).ok_or_else(|| not_found(from))?.clone();
+            add(doc, path, value)
+                .map_err(malformed)?
+                .ok_or_else(|| not_found(path))
+        }
+        "test" => {
+            if doc.pointer(path) == Some(value_member()?) {
+                Ok(())
+            } else {
+                Err(PatchError::TestFailed {
+                    index,
+                    path: path.to_string(),
+                })
+            }
+        }
+        other => Err(invalid(&format!("unknown op \"{other}\""))),
+    }
+}
+
+/// Adds `value` at `path`, or returns `Ok(None)` if its parent does not
+/// exist or cannot take it.
+fn add(doc: &mut Value, path: &str, value: Value) -> Result<Option<()>, PointerError> {
+    if path.is_empty() {
+        *doc = value;
+        return Ok(Some(()));
+    }
+    let (parent, token) = split_last(path)?;
+    let added = match doc.pointer_mut(parent) {
+        Some(Value::Object(obj)) => {
+            obj.insert(token, value);
+            Some(())
+        }
+        Some(Value::Array(arr)) if token == "-" => {
+            arr.push(value);
+            Some(())
+        }
+        Some(Value::Array(arr)) => array_index(&token)
+            .filter(|&i| i <= arr.len())
+            .map(|i| arr.insert(i, value)),
+        _ => None,
+    };
+    Ok(added)
+}
+
+/// Removes the value at `path`, or returns `Ok(None)` if there is none.
+fn remove(doc: &mut Value, path: &str) -> Result<Option<Value>, PointerError> {
+    if path.is_empty() {
+        return Ok(None);
+    }
+    let (parent, token) = split_last(path)?;
+    let removed = match doc.pointer_mut(parent) {
+        Some(Value::Object(obj)) => obj.remove(&token),
+        Some(Value::Array(arr)) => array_index(&token)
+            .filter(|&i| i < arr.len())
+            .map(|i| arr.remove(i)),
+        _ => None,
+    };
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patched(doc: &str, patch: &str) -> Result<Value, PatchError> {
+        let mut v = Value::from(doc);
+        v.apply_patch(&Value::from(patch)).map(|_| v)
+    }
+
+    #[test]
+    fn patch_add() {
+        let v = patched(
+            r#"{"foo": ["bar", "baz"]}"#,
+            r#"[
+                {"op": "add", "path": "/foo/1", "value": "qux"},
+                {"op": "add", "path": "/foo/-", "value": "end"},
+                {"op": "add", "path": "/new", "value": {}}
+            ]"#,
+        );
+        assert_eq!(
+            v,
+            Ok(Value::from(
+                r#"{"foo": ["bar", "qux", "baz", "end"], "new": {}}"#
+            ))
+        );
+    }
+
+    #[test]
+    fn patch_remove() {
+        let v = patched(
+            r#"{"a": 1, "b": [1, 2, 3]}"#,
+            r#"[{"op": "remove", "path": "/a"}, {"op": "remove", "path": "/b/1"}]"#,
+        );
+        assert_eq!(v, Ok(Value::from(r#"{"b": [1, 3]}"#)));
+    }
+
+    #[test]
+    fn patch_replace() {
+        let v = patched(
+            r#"{"a": {"b": 1}}"#,
+            r#"[{"op": "replace", "path": "/a/b", "value": [true]}]"#,
+        );
+        assert_eq!(v, Ok(Value::from(r#"{"a": {"b": [true]}}"#)));
+    }
+
+    #[test]
+    fn patch_move() {
+        let v = patched(
+            r#"{"a": {"b": 1}, "c": []}"#,
+            r#"[{"op": "move", "from": "/a/b", "path": "/c/0"}]"#,
+        );
+        assert_eq!(v, Ok(Value::from(r#"{"a": {}, "c": [1]}"#)));
+    }
+
+    #[test]
+    fn patch_copy() {
+        let v = patched(
+            r#"{"a": {"b": 1}}"#,
+            r#"[{"op": "copy", "from": "/a", "path": "/c"}]"#,
+        );
+        assert_eq!(v, Ok(Value::from(r#"{"a": {"b": 1}, "c": {"b": 1}}"#)));
+    }
+
+    #[test]
+    fn patch_test() {
+        let v = patched(
+            r#"{"a": [1, "x"]}"#,
+            r#"[{"op": "test", "path": "/a/1", "value": "x"}]"#,
+        );
+        assert_eq!(v, Ok(Value::from(r#"{"a": [1, "x"]}"#)));
+    }
+
+    #[test]
+    fn patch_failing_test_leaves_document_unchanged() {
+        let mut v = Value::from(r#"{"a": 1}"#);
+        let patch = Value::from(
+            r#"[
+                {"op": "replace", "path": "/a", "value": 2},
+                {"op": "test", "path": "/a", "value": 3}
+            ]"#,
+        );
+        assert_eq!(
+            v.apply_patch(&patch),
+            Err(PatchError::TestFailed {
+                index: 1,
+                path: "/a".to_string()
+            })
+        );
+        assert_eq!(v, Value::from(r#"{"a": 1}"#));
+    }
+
+    #[test]
+    fn patch_errors() {
+        assert_eq!(
+            patched("{}", r#"[{"op": "remove", "path": "/missing"}]"#),
+            Err(PatchError::PathNotFound {
+                index: 0,
+                path: "/missing".to_string()
+            })
+        );
+        assert!(matches!(
+            patched("{}", r#"[{"op": "frobnicate", "path": ""}]"#),
+            Err(PatchError::InvalidOperation { index: 0, .. })
+        ));
+        assert!(matches!(
+            patched(
+                r#"{"a": {}}"#,
+                r#"[{"op": "move", "from": "/a", "path": "/a/b"}]"#
+            ),
+            Err(PatchError::InvalidOperation { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn patch_rejects_malformed_pointers() {
+        let doc = r#"{"a~2": 1, "b": [1]}"#;
+        for op in [
+            r#"{"op": "add", "path": "/a~2", "value": 2}"#,
+            r#"{"op": "remove", "path": "/a~2"}"#,
+            r#"{"op": "replace", "path": "/a~2", "value": 2}"#,
+            r#"{"op": "test", "path": "/a~2", "value": 1}"#,
+            r#"{"op": "add", "path": "/b~/0", "value": 2}"#,
+            r#"{"op": "copy", "from": "/a~2", "path": "/c"}"#,
+            r#"{"op": "move", "from": "/b", "path": "c"}"#,
+        ] {
+            let err = patched(doc, &format!("[{op}]")).unwrap_err();
+            assert!(
+                matches!(err, PatchError::InvalidOperation { index: 0, .. }),
+                "{op}: {err}"
+            );
+        }
+        assert_eq!(
+            patched(doc, r#"[{"op": "remove", "path": "/a~2/x"}]"#)
+                .unwrap_err()
+                .to_string(),
+            "invalid operation 0: invalid '~' escape in pointer token \"a~2\""
+        );
+    }
+
+    #[test]
+    fn diff_operations() {
+        let a = Value::from(r#"{"a": 1, "b": {"c": 2}, "d": [1, 2, 3]}"#);
+        let b = Value::from(r#"{"a": 1, "b": {"c": 3, "e/f": 4}, "d": [1]}"#);
+        assert_eq!(
+            Value::diff(&a, &b),
+            Value::from(
+                r#"[
+                    {"op": "replace", "path": "/b/c", "value": 3},
+                    {"op": "add", "path": "/b/e~1f", "value": 4},
+                    {"op": "remove", "path": "/d/2"},
+                    {"op": "remove", "path": "/d/1"}
+                ]"#
+            )
+        );
+        assert_eq!(Value::diff(&a, &a), Value::Array(vec![]));
+    }
+
+    #[test]
+    fn diff_round_trips_through_apply_patch() {
+        let cases = [
+            (
+                r#"{"a": [1, {"b": 2}], "c": "x"}"#,
+                r#"{"a": [1, {"b": 3}, 4], "d": null}"#,
+            ),
+            (r#"[1, 2, 3]"#, r#"[3]"#),
+            (r#"{"a": {}}"#, r#"{"a": []}"#),
+            (r#""x""#, r#"{"a": 1}"#),
+        ];
+        for (a, b) in cases {
+            let (a, b) = (Value::from(a), Value::from(b));
+            let mut patched = a.clone();
+            patched.apply_patch(&Value::diff(&a, &b)).unwrap();
+            assert_eq!(patched, b);
+        }
+    }
+}
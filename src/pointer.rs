@@ -0,0 +1,382 @@
+use crate::{Map, Value, map::Entry};
+
+/// Error returned when a JSON Pointer is malformed or cannot be used to
+/// store a value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointerError {
+    /// The pointer is neither empty nor starts with `/`.
+    MissingLeadingSlash,
+    /// A `~` in `token` is not followed by `0` or `1`.
+    InvalidEscape { token: String },
+    /// The value at `path` is a scalar, so nothing can be stored below it.
+    NotAContainer { path: String },
+    /// The token ending `path` is not `-` or an index at most the array length.
+    InvalidIndex { path: String },
+}
+
+impl std::fmt::Display for PointerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PointerError::MissingLeadingSlash => {
+                write!(f, "pointer must be empty or start with '/'")
+            }
+            PointerError::InvalidEscape { token } => {
+                write!(f, "invalid '~' escape in pointer token \"{token}\"")
+            }
+            PointerError::NotAContainer { path } => {
+                write!(f, "value at \"{path}\" is not an object or array")
+            }
+            PointerError::InvalidIndex { path } => {
+                write!(f, "invalid array index at \"{path}\"")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PointerError {}
+
+impl Value {
+    /// Looks up a value by an RFC 6901 JSON Pointer such as `/Image/IDs/0`.
+    ///
+    /// The empty pointer refers to the whole document. Returns `None` when
+    /// the pointer is malformed or does not resolve; use
+    /// [`Value::try_pointer`] to tell the two apart.
+    pub fn pointer(&self, ptr: &str) -> Option<&Value> {
+        self.try_pointer(ptr).ok().flatten()
+    }
+
+    /// Like [`Value::pointer`], but a malformed pointer, such as `a/b` or one
+    /// with the escape `~2`, is an error rather than `None`. `Ok(None)` means
+    /// the pointer is valid but nothing is there.
+    pub fn try_pointer(&self, ptr: &str) -> Result<Option<&Value>, PointerError> {
+        let tokens = tokens(ptr)?;
+        Ok(tokens.iter().try_fold(self, |target, token| match target {
+            Value::Object(obj) => obj.get(token),
+            Value::Array(arr) => arr.get(array_index(token)?),
+            _ => None,
+        }))
+    }
+
+    /// Like [`Value::pointer`], returning a mutable reference.
+    pub fn pointer_mut(&mut self, ptr: &str) -> Option<&mut Value> {
+        if ptr.is_empty() {
+            return Some(self);
+        }
+        let ptr = ptr.strip_prefix('/')?;
+        ptr.split('/').try_fold(self, |target, token| {
+            let token = try_unescape_token(token).ok()?;
+            match target {
+                Value::Object(obj) => obj.get_mut(&token),
+                Value::Array(arr) => arr.get_mut(array_index(&token)?),
+                _ => None,
+            }
+        })
+    }
+
+    /// Looks up a value by a dotted path such as `Image.IDs.0`, where numeric
+    /// segments index arrays. The empty path refers to the whole document.
+    ///
+    /// Keys containing `.` cannot be addressed this way; use
+    /// [`Value::pointer`] for those.
+    pub fn get_path(&self, path: &str) -> Option<&Value> {
+        if path.is_empty() {
+            return Some(self);
+        }
+        path.split('.')
+            .try_fold(self, |target, segment| match target {
+                Value::Object(obj) => obj.get(segment),
+                Value::Array(arr) => arr.get(array_index(segment)?),
+                _ => None,
+            })
+    }
+
+    /// Stores `value` at a JSON Pointer, creating missing intermediate
+    /// containers like `mkdir -p`: an array when the next token is `-` or an
+    /// index, an object otherwise.
+    ///
+    /// An existing value at the pointer is replaced. In arrays, `-` or an
+    /// index equal to the length appends. On error, containers created before
+    /// the failing token are left in place.
+    pub fn set_pointer(&mut self, ptr: &str, value: Value) -> Result<(), PointerError> {
+        if ptr.is_empty() {
+            *self = value;
+            return Ok(());
+        }
+        let tokens: Vec<&str> = ptr
+            .strip_prefix('/')
+            .ok_or(PointerError::MissingLeadingSlash)?
+            .split('/')
+            .collect();
+        let path = |end: usize| match end {
+            0 => String::new(),
+            _ => format!("/{}", tokens[..end].join("/")),
+        };
+
+        let mut target = self;
+        for (i, raw) in tokens.iter().enumerate() {
+            let fresh = || match tokens.get(i + 1) {
+                None => Value::Null,
+                Some(&next) if next == "-" || array_index(next).is_some() => {
+                    Value::Array(Vec::new())
+                }
+                Some(_) => Value::Object(Map::new()),
+            };
+            let token = try_unescape_token(raw)?;
+            target = match target {
+                Value::Object(obj) => match obj.entry(token) {
+                    Entry::Occupied(e) => e.into_mut(),
+                    Entry::Vacant(e) => e.insert(fresh()),
+                },
+                Value::Array(arr) => {
+                    let index = match token.as_str() {
+                        "-" => arr.len(),
+                        _ => array_index(&token)
+                            .filter(|&index| index <= arr.len())
+                            .ok_or_else(|| PointerError::InvalidIndex { path: path(i + 1) })?,
+                    };
+                    if index == arr.len() {
+                        arr.push(fresh());
+                    }
+                    &mut arr[index]
+                }
+                _ => return Err(PointerError::NotAContainer { path: path(i) }),
+            };
+        }
+        *target = value;
+        Ok(())
+    }
+}
+
+/// The unescaped reference tokens of a pointer, none for the empty pointer.
+pub(crate) fn tokens(ptr: &str) -> Result<Vec<String>, PointerError> {
+    if ptr.is_empty() {
+        return Ok(Vec::new());
+    }
+    ptr.strip_prefix('/')
+        .ok_or(PointerError::MissingLeadingSlash)?
+        .split('/')
+        .map(try_unescape_token)
+        .collect()
+}
+
+/// Splits a non-empty pointer into the pointer to the parent and the
+/// unescaped last token.
+pub(crate) fn split_last(ptr: &str) -> Result<(&str, String), PointerError> {
+    match ptr.rsplit_once('/') {
+        Some((parent, last)) if ptr.starts_with('/') => Ok((parent, try_unescape_token(last)?)),
+        _ => Err(PointerError::MissingLeadingSlash),
+    }
+}
+
+pub(crate) fn unescape_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Like [`unescape_token`], rejecting a `~` not followed by `0` or `1`.
+pub(crate) fn try_unescape_token(token: &str) -> Result<String, PointerError> {
+    let mut rest = token;
+    while let Some(i) = rest.find('~') {
+        rest = match rest[i + 1..].strip_prefix(['0', '1']) {
+            Some(after) => after,
+            None => {
+                return Err(PointerError::InvalidEscape {
+                    token: token.to_string(),
+                });
+            }
+        };
+    }
+    Ok(unescape_token(token))
+}
+
+/// Escapes `~` and `/` in an object key for use as a pointer token.
+pub(crate) fn escape_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Array indices are decimal without leading zeros.
+pub(crate) fn array_index(token: &str) -> Option<usize> {
+    if token.is_empty()
+        || (token.len() > 1 && token.starts_with('0'))
+        || !token.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    token.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_and_unescape_tokens() {
+        assert_eq!(crate::escape_pointer_token("a/b~c"), "a~1b~0c");
+        assert_eq!(
+            crate::unescape_pointer_token("a~1b~0c"),
+            Ok("a/b~c".to_string())
+        );
+        // `~01` is an escaped `~` followed by `1`, not an escaped `/`.
+        assert_eq!(crate::escape_pointer_token("~1"), "~01");
+        assert_eq!(crate::unescape_pointer_token("~01"), Ok("~1".to_string()));
+        assert_eq!(
+            crate::unescape_pointer_token("a~2"),
+            Err(PointerError::InvalidEscape {
+                token: "a~2".to_string()
+            })
+        );
+
+        let v = Value::from(r#"{"a/b~c": {"": 1}}"#);
+        let ptr = format!("/{}/", crate::escape_pointer_token("a/b~c"));
+        assert_eq!(v.pointer(&ptr), Some(&Value::Number(1.0)));
+    }
+
+    #[test]
+    fn pointer_into_nested_document() {
+        let v = Value::from(r#"{"Image": {"Thumbnail": {"Url": "x"}, "IDs": [116, 943]}}"#);
+        assert_eq!(
+            v.pointer("/Image/Thumbnail/Url"),
+            Some(&Value::String("x".to_string()))
+        );
+        assert_eq!(v.pointer("/Image/IDs/1"), Some(&Value::Number(943.0)));
+        assert_eq!(v.pointer("/Image/IDs/2"), None);
+        assert_eq!(v.pointer("/Image/IDs/01"), None);
+        assert_eq!(v.pointer("/Image/Missing"), None);
+        assert_eq!(v.pointer("Image"), None);
+    }
+
+    #[test]
+    fn get_path_on_rfc_example() {
+        let v = Value::from(
+            r#"{
+                "Image": {
+                    "Width":  800,
+                    "Title":  "View from 15th Floor",
+                    "Thumbnail": {
+                        "Url":    "http://www.example.com/image/481989943",
+                        "Width":  100
+                    },
+                    "IDs": [116, 943, 234, 38793]
+                },
+                "a.b": 1
+            }"#,
+        );
+        assert_eq!(
+            v.get_path("Image.Thumbnail.Width"),
+            Some(&Value::Number(100.0))
+        );
+        assert_eq!(v.get_path("Image.IDs.3"), Some(&Value::Number(38793.0)));
+        assert_eq!(v.get_path("Image.IDs.4"), None);
+        assert_eq!(v.get_path("Image.Title.0"), None);
+        assert_eq!(v.get_path("a.b"), None);
+        assert_eq!(v.pointer("/a.b"), Some(&Value::Number(1.0)));
+        assert_eq!(v.get_path(""), Some(&v));
+    }
+
+    #[test]
+    fn root_and_empty_key_pointers() {
+        let v = Value::from(r#"{"": {"": 1, "a": 2}, "a": 3}"#);
+        assert_eq!(v.pointer(""), Some(&v));
+        assert_eq!(v.pointer("/"), Some(&Value::from(r#"{"": 1, "a": 2}"#)));
+        assert_eq!(v.pointer("//"), Some(&Value::Number(1.0)));
+        assert_eq!(v.pointer("//a"), Some(&Value::Number(2.0)));
+        assert_eq!(v.pointer("/a"), Some(&Value::Number(3.0)));
+        assert_eq!(v.pointer("a"), None);
+        assert_eq!(Value::from(r#"{"a": 1}"#).pointer("/"), None);
+    }
+
+    #[test]
+    fn pointer_mut_edits_in_place() {
+        let mut v = Value::from(r#"{"a": [1, {"b": 2}]}"#);
+        *v.pointer_mut("/a/1/b").unwrap() = Value::Null;
+        assert_eq!(v, Value::from(r#"{"a": [1, {"b": null}]}"#));
+        assert_eq!(v.pointer_mut("/a/2"), None);
+    }
+
+    #[test]
+    fn set_pointer_creates_intermediate_containers() {
+        let mut v = Value::from("{}");
+        v.set_pointer("/a/b/c", Value::Number(1.0)).unwrap();
+        assert_eq!(v, Value::from(r#"{"a": {"b": {"c": 1}}}"#));
+
+        v.set_pointer("/a/b/c", Value::Boolean(true)).unwrap();
+        v.set_pointer("/a/list/-/x~1y", Value::Null).unwrap();
+        v.set_pointer("/a/list/1", Value::Number(2.0)).unwrap();
+        v.set_pointer("/a/list/0/z", Value::Number(3.0)).unwrap();
+        assert_eq!(
+            v,
+            Value::from(r#"{"a": {"b": {"c": true}, "list": [{"x/y": null, "z": 3}, 2]}}"#)
+        );
+
+        let mut root = Value::Null;
+        root.set_pointer("", Value::from("[]")).unwrap();
+        assert_eq!(root, Value::Array(vec![]));
+    }
+
+    #[test]
+    fn set_pointer_errors() {
+        let mut v = Value::from(r#"{"a": 1, "b": [0]}"#);
+        assert_eq!(
+            v.set_pointer("a", Value::Null),
+            Err(PointerError::MissingLeadingSlash)
+        );
+        assert_eq!(
+            v.set_pointer("/a/x", Value::Null),
+            Err(PointerError::NotAContainer {
+                path: "/a".to_string()
+            })
+        );
+        assert_eq!(
+            v.set_pointer("/b/2", Value::Null),
+            Err(PointerError::InvalidIndex {
+                path: "/b/2".to_string()
+            })
+        );
+        assert_eq!(
+            v.set_pointer("/b/x/y", Value::Null),
+            Err(PointerError::InvalidIndex {
+                path: "/b/x".to_string()
+            })
+        );
+        assert_eq!(v, Value::from(r#"{"a": 1, "b": [0]}"#));
+        assert_eq!(
+            Value::Number(1.0).set_pointer("/a", Value::Null),
+            Err(PointerError::NotAContainer {
+                path: String::new()
+            })
+        );
+    }
+
+    #[test]
+    fn pointer_escapes() {
+        let v = Value::from(r#"{"a/b": 1, "m~n": 2, "~1": 3}"#);
+        assert_eq!(v.pointer("/a~1b"), Some(&Value::Number(1.0)));
+        assert_eq!(v.pointer("/m~0n"), Some(&Value::Number(2.0)));
+        assert_eq!(v.pointer("/~01"), Some(&Value::Number(3.0)));
+    }
+
+    #[test]
+    fn try_pointer_tells_malformed_from_missing() {
+        let v = Value::from(r#"{"a": {"b~": 1}, "~2": 2}"#);
+        assert_eq!(v.try_pointer("/a/b~0"), Ok(Some(&Value::Number(1.0))));
+        assert_eq!(v.try_pointer("/a/c"), Ok(None));
+        assert_eq!(v.try_pointer("/a/b~0/c"), Ok(None));
+        assert_eq!(v.try_pointer("a/b"), Err(PointerError::MissingLeadingSlash));
+
+        let err = v.try_pointer("/~2").unwrap_err();
+        assert_eq!(
+            err,
+            PointerError::InvalidEscape {
+                token: "~2".to_string()
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            r#"invalid '~' escape in pointer token "~2""#
+        );
+        assert!(v.try_pointer("/a/b~").is_err());
+        assert_eq!(v.pointer("/~2"), None);
+
+        let mut v = v;
+        assert!(v.set_pointer("/x~3", Value::Null).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{Value, pointer::escape_token};
+
+/// A place where a value does not match its schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    /// JSON Pointer to the offending value.
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at \"{}\": {}", self.path, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+pub(crate) fn validate(value: &Value, schema: &Value) -> Result<(), Vec<ValidationError>> {
+    let mut errors = Vec::new();
+    check(value, schema, String::new(), &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => Some(*n),
+        Value::RawNumber(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "integer" => number(value).is_some_and(|n| n.fract() == 0.0),
+        name => value.type_name() == name,
+    }
+}
+
+fn check(value: &Value, schema: &Value, path: String, errors: &mut Vec<ValidationError>) {
+    let mut fail = |message: String| {
+        errors.push(ValidationError {
+            path: path.clone(),
+            message,
+        })
+    };
+    let schema = match schema {
+        Value::Boolean(true) => return,
+        Value::Boolean(false) => return fail("no value is allowed here".to_string()),
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+
+    match schema.get("type") {
+        Some(Value::String(name)) if !has_type(value, name) => {
+            return fail(format!("expected {name}, found {}", value.type_name()));
+        }
+        Some(Value::Array(names)) => {
+            let names: Vec<&str> = names
+                .iter()
+                .filter_map(|n| match n {
+                    Value::String(s) => Some(s.as_str()),
+                    _ => None,
+                })
+                .collect();
+            if !names.iter().any(|name| has_type(value, name)) {
+                return fail(format!(
+                    "expected {}, found {}",
+                    names.join(" or "),
+                    value.type_name()
+                ));
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            fail(format!("{value} is not one of the allowed values"));
+        }
+    }
+    if let Some(n) = number(value) {
+        if let Some(min) = schema.get("minimum").and_then(number) {
+            if n < min {
+                fail(format!("{value} is less than the minimum of {min}"));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(number) {
+            if n > max {
+                fail(format!("{value} is greater than the maximum of {max}"));
+            }
+        }
+    }
+
+    match value {
+        Value::Object(obj) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for key in required {
+                    if let Value::String(key) = key {
+                        if !obj.contains_key(key) {
+                            fail(format!("missing required property \"{key}\""));
+                        }
+                    }
+                }
+            }
+            if let Some(Value::Object(properties)) = schema.get("properties") {
+                for (key, sub) in properties {
+                    if let Some(v) = obj.get(key) {
+                        check(v, sub, format!("{path}/{}", escape_token(key)), errors);
+                    }
+                }
+            }
+        }
+        Value::Array(arr) => {
+            if let Some(items) = schema.get("items") {
+                for (i, v) in arr.iter().enumerate() {
+                    check(v, items, format!("{path}/{i}"), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(value: &str, schema: &str) -> Vec<(String, String)> {
+        match validate(&Value::from(value), &Value::from(schema)) {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.into_iter().map(|e| (e.path, e.message)).collect(),
+        }
+    }
+
+    const SCHEMA: &str = r#"{
+        "type": "object",
+        "required": ["name", "port"],
+        "properties": {
+            "name": {"type": "string"},
+            "port": {"type": "integer", "minimum": 1, "maximum": 65535},
+            "mode": {"enum": ["dev", "prod"]},
+            "hosts": {"type": "array", "items": {"type": ["string", "null"]}}
+        }
+    }"#;
+
+    #[test]
+    fn valid_document() {
+        let config = r#"{"name": "api", "port": 8080, "mode": "prod", "hosts": ["a", null]}"#;
+        assert_eq!(errors(config, SCHEMA), vec![]);
+    }
+
+    #[test]
+    fn missing_required_property() {
+        assert_eq!(
+            errors(r#"{"port": 80}"#, SCHEMA),
+            vec![(
+                String::new(),
+                "missing required property \"name\"".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn errors_point_at_nested_values() {
+        let config = r#"{"name": 1, "port": 70000.5, "mode": "test", "hosts": ["a", 2]}"#;
+        let mut found = errors(config, SCHEMA);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "/hosts/1".to_string(),
+                    "expected string or null, found number".to_string()
+                ),
+                (
+                    "/mode".to_string(),
+                    "\"test\" is not one of the allowed values".to_string()
+                ),
+                (
+                    "/name".to_string(),
+                    "expected string, found number".to_string()
+                ),
+                (
+                    "/port".to_string(),
+                    "expected integer, found number".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            errors("70000", r#"{"type": "integer", "maximum": 65535}"#),
+            vec![(
+                String::new(),
+                "70000 is greater than the maximum of 65535".to_string()
+            )]
+        );
+        assert_eq!(
+            errors("[1]", r#"{"items": false}"#),
+            vec![("/0".to_string(), "no value is allowed here".to_string())]
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{FormatOptions, Value, generate::write_escaped_char};
+
+const ELLIPSIS: char = '…';
+
+impl Value {
+    /// Writes a string as a quoted JSON literal of at most `max_chars`
+    /// characters for display, e.g. in a log viewer. Longer strings are cut
+    /// short and end in `…` inside the quotes, and escapes such as `\n` are
+    /// never split. The shortest truncated snippet is `"…"`, even when
+    /// `max_chars` is less than 3.
+    ///
+    /// Returns `None` for values other than strings.
+    pub fn to_snippet(&self, max_chars: usize) -> Option<String> {
+        let s = self.as_str()?;
+
+        let mut out = String::from('"');
+        // Characters written so far, not counting the opening quote.
+        let mut len = 0;
+        let mut piece = String::new();
+        let opts = FormatOptions::default();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            piece.clear();
+            // Writing into a String cannot fail.
+            let _ = write_escaped_char(&mut piece, c, &opts);
+            let piece_len = piece.chars().count();
+            // Keep room for the ellipsis unless this is the last character.
+            let reserve = if chars.as_str().is_empty() { 1 } else { 2 };
+            if 1 + len + piece_len + reserve > max_chars {
+                out.push(ELLIPSIS);
+                break;
+            }
+            out.push_str(&piece);
+            len += piece_len;
+        }
+        out.push('"');
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_long_string() {
+        let long = Value::String("a".repeat(100));
+        let snippet = long.to_snippet(10).unwrap();
+        assert_eq!(snippet, r#""aaaaaaa…""#);
+        assert_eq!(snippet.chars().count(), 10);
+    }
+
+    #[test]
+    fn short_strings_are_unchanged() {
+        let v = Value::String("abcdefgh".into());
+        assert_eq!(v.to_snippet(10).unwrap(), r#""abcdefgh""#);
+        assert_eq!(v.to_snippet(9).unwrap(), r#""abcdef…""#);
+        assert_eq!(Value::String(String::new()).to_snippet(0).unwrap(), r#""""#);
+    }
+
+    #[test]
+    fn escapes_are_not_split() {
+        let v = Value::String("ab\ncd\u{1}".into());
+        assert_eq!(v.to_snippet(100).unwrap(), r#""ab\ncd\u0001""#);
+        // `"ab\n…"` would be 7 characters, one too many.
+        assert_eq!(v.to_snippet(6).unwrap(), r#""ab…""#);
+        assert_eq!(v.to_snippet(7).unwrap(), r#""ab\n…""#);
+        assert_eq!(v.to_snippet(1).unwrap(), r#""…""#);
+    }
+
+    #[test]
+    fn non_strings() {
+        assert_eq!(Value::from("[1]").to_snippet(10), None);
+    }
+}
@@ -0,0 +1,197 @@
+use std::cmp::Ordering;
+
+use crate::{Error, Value, value::decimal};
+
+impl Value {
+    /// Checks whether an array of objects is ordered by the value under `key`.
+    ///
+    /// Numbers compare numerically and strings lexicographically; comparing
+    /// values of different types is an error.
+    pub fn is_sorted_by_key(&self, key: &str, ascending: bool) -> Result<bool, Error> {
+        let Value::Array(arr) = self else {
+            return Err(Error::TypeMismatch {
+                expected: "array",
+                found: self.type_name(),
+            });
+        };
+
+        let mut keys = Vec::with_capacity(arr.len());
+        for v in arr {
+            let Value::Object(obj) = v else {
+                return Err(Error::TypeMismatch {
+                    expected: "object",
+                    found: v.type_name(),
+                });
+            };
+            keys.push(
+                obj.get(key)
+                    .ok_or_else(|| Error::MissingKey(key.to_string()))?,
+            );
+        }
+
+        for pair in keys.windows(2) {
+            let unordered = match loose_cmp(pair[0], pair[1])? {
+                Ordering::Greater => ascending,
+                Ordering::Less => !ascending,
+                Ordering::Equal => false,
+            };
+            if unordered {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Sorts the members of every object in the tree by key, comparing code
+    /// points, so that the stored order no longer depends on the input.
+    pub fn sort_keys(&mut self) {
+        self.transform(|node| {
+            if let Value::Object(obj) = node {
+                obj.sort_keys();
+            }
+        });
+    }
+}
+
+fn loose_cmp(a: &Value, b: &Value) -> Result<Ordering, Error> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => Ok(a.partial_cmp(b).unwrap_or(Ordering::Equal)),
+        (Value::Number(_) | Value::RawNumber(_), Value::Number(_) | Value::RawNumber(_)) => {
+            Ok(number_cmp(a, b))
+        }
+        (Value::String(a), Value::String(b)) => Ok(a.cmp(b)),
+        (Value::Boolean(a), Value::Boolean(b)) => Ok(a.cmp(b)),
+        (Value::Null, Value::Null) => Ok(Ordering::Equal),
+        _ => Err(Error::TypeMismatch {
+            expected: a.type_name(),
+            found: b.type_name(),
+        }),
+    }
+}
+
+/// Compares numbers when at least one is raw, by exact decimal value so that
+/// digits beyond `f64` precision count, falling back to `f64` for infinity
+/// and raw text that is not a number.
+fn number_cmp(a: &Value, b: &Value) -> Ordering {
+    let digits = |v: &Value| match v {
+        Value::Number(n) if n.is_finite() => decimal(&format!("{n:e}")),
+        Value::RawNumber(s) => decimal(s),
+        _ => None,
+    };
+    if let (Some(a), Some(b)) = (digits(a), digits(b)) {
+        return decimal_cmp(&a, &b);
+    }
+    let float = |v: &Value| match v {
+        Value::Number(n) => *n,
+        Value::RawNumber(s) => s.parse().unwrap_or(f64::NAN),
+        _ => f64::NAN,
+    };
+    float(a).partial_cmp(&float(b)).unwrap_or(Ordering::Equal)
+}
+
+fn decimal_cmp(a: &(bool, String, i64), b: &(bool, String, i64)) -> Ordering {
+    let sign = |(negative, digits, _): &(bool, String, i64)| match (digits.is_empty(), negative) {
+        (true, _) => 0,
+        (false, true) => -1,
+        (false, false) => 1,
+    };
+    let magnitude = || {
+        // Both have digits: the one with the larger leading power of ten is
+        // larger, and with equal powers the digits decide.
+        let power = |(_, digits, exp): &(bool, String, i64)| exp + digits.len() as i64;
+        power(a).cmp(&power(b)).then_with(|| a.1.cmp(&b.1))
+    };
+    match sign(a).cmp(&sign(b)) {
+        Ordering::Equal if sign(a) == 0 => Ordering::Equal,
+        Ordering::Equal if a.0 => magnitude().reverse(),
+        Ordering::Equal => magnitude(),
+        unequal => unequal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorted_records() {
+        let v = Value::from(r#"[{"id": 1, "n": "c"}, {"id": 2, "n": "b"}, {"id": 2, "n": "a"}]"#);
+        assert_eq!(v.is_sorted_by_key("id", true), Ok(true));
+        assert_eq!(v.is_sorted_by_key("id", false), Ok(false));
+        assert_eq!(v.is_sorted_by_key("n", false), Ok(true));
+    }
+
+    #[test]
+    fn unsorted_records() {
+        let v = Value::from(r#"[{"id": 3}, {"id": 1}, {"id": 2}]"#);
+        assert_eq!(v.is_sorted_by_key("id", true), Ok(false));
+        assert_eq!(v.is_sorted_by_key("id", false), Ok(false));
+    }
+
+    #[test]
+    fn sorted_by_key_errors() {
+        let v = Value::from(r#"{"id": 1}"#);
+        assert_eq!(
+            v.is_sorted_by_key("id", true),
+            Err(Error::TypeMismatch {
+                expected: "array",
+                found: "object"
+            })
+        );
+
+        let v = Value::from(r#"[{"id": 1}, {"name": "x"}]"#);
+        assert_eq!(
+            v.is_sorted_by_key("id", true),
+            Err(Error::MissingKey("id".to_string()))
+        );
+    }
+
+    #[test]
+    fn sorted_by_raw_numbers() {
+        let sorted = |ids: Vec<Value>| {
+            let records = ids
+                .into_iter()
+                .map(|id| Value::Object([("id".to_string(), id)].into()))
+                .collect();
+            Value::Array(records).is_sorted_by_key("id", true)
+        };
+        let raw = |ids: &str| {
+            ids.split(' ')
+                .map(|id| Value::RawNumber(id.to_string()))
+                .collect::<Vec<_>>()
+        };
+        let ids = "-1e3 -2.5 0 -0.0 1e-1 2 10 1.5e1 12345678901234567890 12345678901234567891";
+        assert_eq!(sorted(raw(ids)), Ok(true));
+        assert_eq!(sorted(raw("10 9.99")), Ok(false));
+        assert_eq!(sorted(raw("-10 -9.99")), Ok(true));
+        assert_eq!(
+            sorted(raw("12345678901234567891 12345678901234567890")),
+            Ok(false)
+        );
+
+        let mixed = |n: f64| {
+            let mut ids = raw("1 3");
+            ids.insert(1, Value::Number(n));
+            sorted(ids)
+        };
+        assert_eq!(mixed(2.0), Ok(true));
+        assert_eq!(mixed(0.5), Ok(false));
+        assert_eq!(mixed(f64::INFINITY), Ok(false));
+    }
+
+    #[test]
+    fn sort_keys_at_every_level() {
+        let mut v = Value::from(r#"{"b": [{"z": 1, "y": {"d": 0, "c": 0}}], "a": null, "B": 1}"#);
+        v.sort_keys();
+        assert_eq!(v.keys().unwrap().collect::<Vec<_>>(), ["B", "a", "b"]);
+        assert_eq!(v["b"][0].keys().unwrap().collect::<Vec<_>>(), ["y", "z"]);
+        assert_eq!(
+            v["b"][0]["y"].keys().unwrap().collect::<Vec<_>>(),
+            ["c", "d"]
+        );
+        assert_eq!(
+            v.to_compact_string(),
+            r#"{"B":1,"a":null,"b":[{"y":{"c":0,"d":0},"z":1}]}"#
+        );
+    }
+}
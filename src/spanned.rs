@@ -0,0 +1,84 @@
+use std::{collections::HashMap, ops::Range};
+
+use crate::Value;
+
+/// A parsed value together with the byte ranges in the source of it and of
+/// every value inside it, as returned by [`parse_spanned`](crate::parse_spanned).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub value: T,
+    /// Byte range of `value` in the input, without surrounding whitespace.
+    pub span: Range<usize>,
+    /// Spans of nested values, keyed by JSON Pointer relative to `value`.
+    children: HashMap<String, Range<usize>>,
+}
+
+impl Spanned<Value> {
+    pub(crate) fn new(value: Value, mut spans: HashMap<String, Range<usize>>) -> Self {
+        let span = spans.remove("").unwrap_or_default();
+        Spanned {
+            value,
+            span,
+            children: spans,
+        }
+    }
+
+    /// Byte range of the value at a JSON Pointer such as `/items/0`, or
+    /// `None` if there is no value there. For an object member this covers
+    /// the member's value, not its key.
+    pub fn span_of(&self, ptr: &str) -> Option<Range<usize>> {
+        if ptr.is_empty() {
+            return Some(self.span.clone());
+        }
+        self.children.get(ptr).cloned()
+    }
+
+    /// The value at a JSON Pointer along with its span.
+    pub fn get(&self, ptr: &str) -> Option<Spanned<&Value>> {
+        Some(Spanned {
+            value: self.value.pointer(ptr)?,
+            span: self.span_of(ptr)?,
+            children: HashMap::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_spanned;
+
+    #[test]
+    fn span_of_member_value() {
+        let input = r#"{"a":"b"}"#;
+        let doc = parse_spanned(input).unwrap();
+        assert_eq!(doc.span, 0..input.len());
+        let span = doc.span_of("/a").unwrap();
+        assert_eq!(&input[span], r#""b""#);
+    }
+
+    #[test]
+    fn spans_of_nested_values() {
+        let input = " {\"list\": [1, {\"a~b\": true}, [] ],\n \"n\": null } ";
+        let doc = parse_spanned(input).unwrap();
+        assert_eq!(&input[doc.span.clone()], input.trim());
+
+        let text = |ptr: &str| &input[doc.span_of(ptr).unwrap()];
+        assert_eq!(text("/list"), r#"[1, {"a~b": true}, [] ]"#);
+        assert_eq!(text("/list/0"), "1");
+        assert_eq!(text("/list/1"), r#"{"a~b": true}"#);
+        assert_eq!(text("/list/1/a~0b"), "true");
+        assert_eq!(text("/list/2"), "[]");
+        assert_eq!(text("/n"), "null");
+        assert_eq!(doc.span_of("/missing"), None);
+
+        let element = doc.get("/list/0").unwrap();
+        assert_eq!(element.value, &crate::Value::Number(1.0));
+        assert_eq!(element.span, 11..12);
+    }
+
+    #[test]
+    fn errors_are_reported_as_by_try_parse() {
+        let err = parse_spanned("[1,]").unwrap_err();
+        assert_eq!(err, crate::try_parse("[1,]").unwrap_err());
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{ErrorKind, ParseError, lexer::is_whitespace};
+
+/// Splits concatenated JSON documents into their source slices.
+///
+/// Only the balance of brackets and quotes is checked, the documents
+/// themselves are not parsed.
+pub(crate) fn split_documents(input: &str) -> Result<Vec<&str>, ParseError> {
+    let bytes = input.as_bytes();
+    let mut docs = Vec::new();
+    let mut pos = 0;
+    loop {
+        while pos < bytes.len() && is_whitespace(bytes[pos] as char) {
+            pos += 1;
+        }
+        if pos == bytes.len() {
+            break;
+        }
+        let end = document_end(input, pos)?;
+        docs.push(&input[pos..end]);
+        pos = end;
+    }
+    Ok(docs)
+}
+
+fn is_delimiter(b: u8) -> bool {
+    matches!(b, b'{' | b'}' | b'[' | b']' | b'"' | b',' | b':')
+}
+
+fn document_end(input: &str, start: usize) -> Result<usize, ParseError> {
+    let bytes = input.as_bytes();
+    match bytes[start] {
+        b'{' | b'[' => {}
+        b'"' => return string_end(input, start),
+        b @ (b'}' | b']') => {
+            return Err(ParseError::new(
+                input,
+                start,
+                ErrorKind::UnexpectedToken { found: b as char },
+                format!("Unexpected '{}'", b as char),
+            ));
+        }
+        _ => {
+            // A top-level scalar runs until the next whitespace or delimiter.
+            let len = bytes[start..]
+                .iter()
+                .position(|&b| is_whitespace(b as char) || is_delimiter(b))
+                .unwrap_or(bytes.len() - start);
+            if len == 0 {
+                let found = bytes[start] as char;
+                return Err(ParseError::new(
+                    input,
+                    start,
+                    ErrorKind::UnexpectedToken { found },
+                    format!("Unexpected '{found}'"),
+                ));
+            }
+            return Ok(start + len);
+        }
+    }
+
+    let mut stack = Vec::new();
+    let mut pos = start;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b @ (b'{' | b'[') => stack.push(b),
+            b @ (b'}' | b']') => {
+                let open = if b == b'}' { b'{' } else { b'[' };
+                if stack.pop() != Some(open) {
+                    return Err(ParseError::new(
+                        input,
+                        pos,
+                        ErrorKind::UnexpectedToken { found: b as char },
+                        format!("Unexpected '{}'", b as char),
+                    ));
+                }
+                if stack.is_empty() {
+                    return Ok(pos + 1);
+                }
+            }
+            b'"' => {
+                pos = string_end(input, pos)?;
+                continue;
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+
+    Err(ParseError::new(
+        input,
+        input.len(),
+        ErrorKind::UnexpectedEof,
+        "Unexpected end of input inside document.",
+    ))
+}
+
+fn string_end(input: &str, start: usize) -> Result<usize, ParseError> {
+    let bytes = input.as_bytes();
+    let mut pos = start + 1;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'\\' => pos += 2,
+            b'"' => return Ok(pos + 1),
+            _ => pos += 1,
+        }
+    }
+    Err(ParseError::new(
+        input,
+        start,
+        ErrorKind::UnexpectedEof,
+        "Unterminated string: missing closing '\"'.",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_adjacent_documents() {
+        let docs = split_documents("{}{}[]").unwrap();
+        assert_eq!(docs, vec!["{}", "{}", "[]"]);
+    }
+
+    #[test]
+    fn split_documents_with_strings_and_scalars() {
+        let json = "{\"a\": \"}{\"}\n[1, [2]] 3 \"x\\\"\" true";
+        let docs = split_documents(json).unwrap();
+        assert_eq!(
+            docs,
+            vec!["{\"a\": \"}{\"}", "[1, [2]]", "3", "\"x\\\"\"", "true"]
+        );
+    }
+
+    #[test]
+    fn split_unbalanced_document() {
+        let err = split_documents("{}[}").unwrap_err();
+        assert_eq!(err.offset(), 3);
+
+        let err = split_documents("{}{\"a\":").unwrap_err();
+        assert_eq!(err.offset(), 7);
+
+        let err = split_documents("[1],[2]").unwrap_err();
+        assert_eq!(err.offset(), 3);
+        assert_eq!(err.kind(), &ErrorKind::UnexpectedToken { found: ',' });
+
+        let err = split_documents("1:2").unwrap_err();
+        assert_eq!(err.offset(), 1);
+    }
+}
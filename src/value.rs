@@ -1,15 +1,338 @@
-use std::{collections::HashMap, ops::Index};
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    ops::Index,
+};
 
-#[derive(Debug, PartialEq)]
+use crate::{
+    Error, Map,
+    map::{self, Entry},
+};
+
+#[derive(Debug, Clone, Default)]
 pub enum Value {
     String(String),
     Number(f64),
+    /// A number kept as its source text, see [`ParseOptions::raw_numbers`](crate::ParseOptions::raw_numbers).
+    RawNumber(String),
     Boolean(bool),
+    #[default]
     Null,
-    Object(HashMap<String, Value>),
+    Object(Map),
     Array(Vec<Value>),
 }
 
+/// The JSON type of a [`Value`], where [`Value::Number`] and
+/// [`Value::RawNumber`] are both numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    String,
+    Number,
+    Boolean,
+    Null,
+    Object,
+    Array,
+}
+
+impl ValueKind {
+    /// Name of the type, e.g. `"object"`, for diagnostics.
+    pub fn name(self) -> &'static str {
+        match self {
+            ValueKind::String => "string",
+            ValueKind::Number => "number",
+            ValueKind::Boolean => "boolean",
+            ValueKind::Null => "null",
+            ValueKind::Object => "object",
+            ValueKind::Array => "array",
+        }
+    }
+}
+
+impl Value {
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Value::String(_) => ValueKind::String,
+            Value::Number(_) | Value::RawNumber(_) => ValueKind::Number,
+            Value::Boolean(_) => ValueKind::Boolean,
+            Value::Null => ValueKind::Null,
+            Value::Object(_) => ValueKind::Object,
+            Value::Array(_) => ValueKind::Array,
+        }
+    }
+
+    /// Name of the JSON type of this value, e.g. `"object"`, for diagnostics.
+    pub fn type_name(&self) -> &'static str {
+        self.kind().name()
+    }
+
+    /// Iterates over the keys of an object, or returns `None` for other types.
+    pub fn keys(&self) -> Option<impl Iterator<Item = &str>> {
+        match self {
+            Value::Object(obj) => Some(obj.keys()),
+            _ => None,
+        }
+    }
+
+    /// Iterates over the member values of an object, or returns `None` for
+    /// other types.
+    pub fn values(&self) -> Option<impl Iterator<Item = &Value>> {
+        match self {
+            Value::Object(obj) => Some(obj.values()),
+            _ => None,
+        }
+    }
+
+    /// Iterates over the key-value pairs of an object in order, or returns
+    /// `None` for other types.
+    pub fn entries(&self) -> Option<map::Iter<'_>> {
+        match self {
+            Value::Object(obj) => Some(obj.iter()),
+            _ => None,
+        }
+    }
+
+    /// Number of elements in an array or members in an object, or `None` for
+    /// scalars.
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            Value::Array(arr) => Some(arr.len()),
+            Value::Object(obj) => Some(obj.len()),
+            _ => None,
+        }
+    }
+
+    /// Whether this is an empty array or object. Scalars are never empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// Whether this is an object with a member named `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        match self {
+            Value::Object(obj) => obj.contains_key(key),
+            _ => false,
+        }
+    }
+
+    /// The entry for `key` in an object, for inserting or updating a member
+    /// with a single lookup, or `None` for other types.
+    pub fn entry(&mut self, key: &str) -> Option<Entry<'_>> {
+        match self {
+            Value::Object(obj) => Some(obj.entry(key.to_string())),
+            _ => None,
+        }
+    }
+
+    /// Decodes the escapes in the body of a JSON string, without its quotes,
+    /// e.g. a fragment taken from JSON text, into a [`Value::String`].
+    ///
+    /// Constructing `Value::String` directly keeps the text as is, so that a
+    /// `\n` in it is escaped again when serialized.
+    ///
+    /// ```
+    /// use rj::Value;
+    ///
+    /// assert_eq!(Value::raw_string(r"a\n"), Ok(Value::String("a\n".to_string())));
+    /// assert_eq!(Value::String(r"a\n".to_string()).to_compact_string(), r#""a\\n""#);
+    /// ```
+    pub fn raw_string(escaped: &str) -> Result<Value, crate::ParseError> {
+        crate::parse::raw_string(escaped)
+    }
+
+    /// Appends an element to an array, or fails for other types.
+    pub fn push(&mut self, value: Value) -> Result<(), Error> {
+        match self {
+            Value::Array(arr) => {
+                arr.push(value);
+                Ok(())
+            }
+            _ => Err(Error::TypeMismatch {
+                expected: "array",
+                found: self.type_name(),
+            }),
+        }
+    }
+
+    /// Inserts a member into an object, returning the previous value of the
+    /// key, or fails for other types.
+    pub fn insert(&mut self, key: String, value: Value) -> Result<Option<Value>, Error> {
+        match self {
+            Value::Object(obj) => Ok(obj.insert(key, value)),
+            _ => Err(Error::TypeMismatch {
+                expected: "object",
+                found: self.type_name(),
+            }),
+        }
+    }
+
+    /// Borrows the contents of a string, or returns `None` for other types.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The number as an `i64` if it is whole and in range, or `None` for
+    /// other numbers and types. Unlike `as i64`, nothing is truncated.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Number(n) => whole(*n, i64::MIN as f64, i64::MAX as f64).map(|n| n as i64),
+            Value::RawNumber(s) => s.parse().ok().or_else(|| {
+                let n = s.parse().ok()?;
+                Value::Number(n).as_i64()
+            }),
+            _ => None,
+        }
+    }
+
+    /// The number as a `u64` if it is whole and in range, or `None` for
+    /// other numbers and types.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Number(n) => whole(*n, 0.0, u64::MAX as f64).map(|n| n as u64),
+            Value::RawNumber(s) => s.parse().ok().or_else(|| {
+                let n = s.parse().ok()?;
+                Value::Number(n).as_u64()
+            }),
+            _ => None,
+        }
+    }
+
+    /// Replaces the value with `Null` and returns the old value, like
+    /// [`std::mem::take`].
+    pub fn take(&mut self) -> Value {
+        std::mem::take(self)
+    }
+
+    /// Takes the elements out of an array, or returns `None` for other types.
+    pub fn into_array(self) -> Option<Vec<Value>> {
+        match self {
+            Value::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    /// Takes the members out of an object, or returns `None` for other types.
+    pub fn into_object(self) -> Option<Map> {
+        match self {
+            Value::Object(obj) => Some(obj),
+            _ => None,
+        }
+    }
+
+    /// Takes the contents out of a string, or returns `None` for other types.
+    pub fn into_string(self) -> Option<String> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Numbers compare by value, not by spelling: `10`, `10.0` and `1e1` are all
+/// equal, whether they were parsed into [`Value::Number`] or kept as
+/// [`Value::RawNumber`]. Two raw numbers compare their exact decimal values,
+/// so digits beyond `f64` precision still tell them apart, and a
+/// `Value::Number` compares as its shortest round-tripping decimal, so
+/// `RawNumber("0.1")` equals `Number(0.1)`. Raw text that is not a number
+/// only equals the same text.
+///
+/// Unlike for `f64`, `Number(NAN)` equals itself, so that `Value` can be `Eq`.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Number(a), Value::Number(b)) => a == b || a.is_nan() && b.is_nan(),
+            (Value::Number(n), Value::RawNumber(s)) | (Value::RawNumber(s), Value::Number(n)) => {
+                n.is_finite() && decimal(&format!("{n:e}")) == decimal(s)
+            }
+            (Value::RawNumber(a), Value::RawNumber(b)) => match (decimal(a), decimal(b)) {
+                (Some(x), Some(y)) => x == y,
+                _ => a == b,
+            },
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::Null, Value::Null) => true,
+            (Value::Object(a), Value::Object(b)) => a == b,
+            (Value::Array(a), Value::Array(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// Normalizes number text to `(negative, significant digits, exponent)`,
+/// with zero always `(false, "", 0)`. Text that is not a number yields `None`.
+pub(crate) fn decimal(text: &str) -> Option<(bool, String, i64)> {
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (mantissa, exp) = match text.find(['e', 'E']) {
+        Some(i) => (&text[..i], text[i + 1..].parse::<i64>().ok()?),
+        None => (text, 0),
+    };
+    let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if int.is_empty() || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let digits = format!("{int}{frac}");
+    let digits = digits.trim_start_matches('0');
+    let trimmed = digits.trim_end_matches('0');
+    if trimmed.is_empty() {
+        return Some((false, String::new(), 0));
+    }
+    let exp = exp - frac.len() as i64 + (digits.len() - trimmed.len()) as i64;
+    Some((negative, trimmed.to_string(), exp))
+}
+
+/// Every value equals itself, `NaN` included, so values are safe to use as
+/// `HashSet` keys.
+impl Eq for Value {}
+
+/// Consistent with `PartialEq`: `0.0` and `-0.0` hash alike, numbers hash
+/// by value whichever variant holds them, and objects hash the same
+/// regardless of member order.
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Value::RawNumber(s) => {
+                // Equal raw numbers parse to the same `f64`, so hashing that
+                // keeps them in the same bucket as the matching `Number`.
+                Value::Number(s.parse().unwrap_or(f64::NAN)).hash(state);
+                return;
+            }
+            _ => std::mem::discriminant(self).hash(state),
+        }
+        match self {
+            Value::String(s) => s.hash(state),
+            Value::Number(n) => {
+                let n = if *n == 0.0 {
+                    0.0
+                } else if n.is_nan() {
+                    f64::NAN
+                } else {
+                    *n
+                };
+                n.to_bits().hash(state);
+            }
+            Value::RawNumber(_) => unreachable!(),
+            Value::Boolean(b) => b.hash(state),
+            Value::Null => {}
+            Value::Object(obj) => {
+                // Sum the member hashes so iteration order does not matter.
+                let mut sum: u64 = 0;
+                for member in obj {
+                    let mut h = DefaultHasher::new();
+                    member.hash(&mut h);
+                    sum = sum.wrapping_add(h.finish());
+                }
+                obj.len().hash(state);
+                sum.hash(state);
+            }
+            Value::Array(arr) => arr.hash(state),
+        }
+    }
+}
+
 impl Index<&str> for Value {
     type Output = Value;
 
@@ -34,8 +357,342 @@ impl Index<usize> for Value {
     }
 }
 
+macro_rules! try_from_value {
+    ($ty:ty, $variant:ident, $expected:literal) => {
+        impl TryFrom<Value> for $ty {
+            type Error = Error;
+
+            fn try_from(value: Value) -> Result<Self, Self::Error> {
+                match value {
+                    Value::$variant(x) => Ok(x),
+                    other => Err(Error::TypeMismatch {
+                        expected: $expected,
+                        found: other.type_name(),
+                    }),
+                }
+            }
+        }
+    };
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Number(n) => Ok(n),
+            Value::RawNumber(s) => Ok(s.parse().unwrap_or(f64::NAN)),
+            other => Err(Error::TypeMismatch {
+                expected: "number",
+                found: other.type_name(),
+            }),
+        }
+    }
+}
+
+try_from_value!(String, String, "string");
+try_from_value!(bool, Boolean, "boolean");
+try_from_value!(Vec<Value>, Array, "array");
+
+/// `n` if it has no fractional part and lies in `min..max`. The bounds are
+/// powers of two, so `max` itself is just out of range.
+fn whole(n: f64, min: f64, max: f64) -> Option<f64> {
+    (n.fract() == 0.0 && n >= min && n < max).then_some(n)
+}
+
+/// Iterates over the elements of an array. Other values, including objects,
+/// yield nothing; see [`Value::entries`] for the members of an object.
+impl<'a> IntoIterator for &'a Value {
+    type Item = &'a Value;
+    type IntoIter = std::slice::Iter<'a, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            Value::Array(arr) => arr.iter(),
+            _ => [].iter(),
+        }
+    }
+}
+
+/// Parses the text as JSON, panicking if it is invalid. Prefer
+/// [`str::parse`], which returns the [`ParseError`](crate::ParseError).
 impl From<&str> for Value {
     fn from(value: &str) -> Self {
         crate::parse(value)
     }
 }
+
+impl std::str::FromStr for Value {
+    type Err = crate::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::try_parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedup_with_hash_set() {
+        use std::collections::HashSet;
+
+        let records =
+            Value::from(r#"[{"a": 1, "b": [0]}, {"b": [-0], "a": 1.0}, {"a": 2}, null, null]"#);
+        let unique: HashSet<Value> = records.into_array().unwrap().into_iter().collect();
+        assert_eq!(unique.len(), 3);
+        assert!(unique.contains(&Value::from(r#"{"a": 2}"#)));
+    }
+
+    #[test]
+    fn numbers_compare_by_value() {
+        // The default parser stores all three as the same `f64`.
+        assert_eq!(Value::from("10"), Value::from("10.0"));
+        assert_eq!(Value::from("10"), Value::from("1e1"));
+        assert_ne!(Value::from("10"), Value::from("10.5"));
+        assert_eq!(Value::from("-0"), Value::from("0"));
+
+        let raw = |s: &str| {
+            let opts = crate::ParseOptions {
+                raw_numbers: true,
+                ..Default::default()
+            };
+            crate::try_parse_with(s, &opts).unwrap()
+        };
+        assert_eq!(raw("10"), raw("10.0"));
+        assert_eq!(raw("10"), raw("1e1"));
+        assert_eq!(raw("1.50"), raw("15E-1"));
+        assert_eq!(raw("-0.0"), raw("0e5"));
+        assert_ne!(raw("10"), raw("-10"));
+        assert_eq!(raw("10"), Value::Number(10.0));
+        assert_eq!(Value::Number(0.1), raw("0.1"));
+        assert_ne!(raw("10"), Value::String("10".into()));
+
+        // Raw numbers keep digits that `f64` would round away.
+        let big = raw("12345678901234567890");
+        assert_ne!(big, raw("12345678901234567891"));
+        assert_ne!(big, Value::Number(12345678901234567890.0));
+
+        // Text that is not a number, only possible when built by hand, is
+        // compared as text.
+        let text = |s: &str| Value::RawNumber(s.to_string());
+        assert_ne!(text("abc"), text("xyz"));
+        assert_eq!(text("abc"), text("abc"));
+        assert_ne!(text("abc"), raw("1"));
+    }
+
+    #[test]
+    fn equal_numbers_hash_alike() {
+        use std::collections::HashSet;
+
+        let opts = crate::ParseOptions {
+            raw_numbers: true,
+            ..Default::default()
+        };
+        let mut set: HashSet<Value> = crate::try_parse_with("[10, 10.0, 1e1, 100e-1]", &opts)
+            .unwrap()
+            .into_array()
+            .unwrap()
+            .into_iter()
+            .collect();
+        set.insert(Value::Number(10.0));
+        assert_eq!(set.len(), 1);
+
+        let nan = Value::Array(vec![Value::Number(f64::NAN)]);
+        assert_eq!(nan, nan.clone());
+        set.insert(nan.clone());
+        set.insert(nan);
+        assert_eq!(set.len(), 2);
+        assert_ne!(Value::Number(f64::NAN), Value::Number(1.0));
+    }
+
+    #[test]
+    fn type_names() {
+        let v = Value::from(r#"["s", 1, true, null, {}, []]"#);
+        let names: Vec<&str> = v
+            .into_array()
+            .unwrap()
+            .iter()
+            .map(Value::type_name)
+            .collect();
+        assert_eq!(
+            names,
+            ["string", "number", "boolean", "null", "object", "array"]
+        );
+    }
+
+    #[test]
+    fn try_into_scalars() {
+        let n: f64 = Value::from("1.5").try_into().unwrap();
+        assert_eq!(n, 1.5);
+        let b: bool = Value::from("true").try_into().unwrap();
+        assert!(b);
+        let arr: Vec<Value> = Value::from("[null]").try_into().unwrap();
+        assert_eq!(arr, vec![Value::Null]);
+
+        let err = f64::try_from(Value::from(r#""1""#)).unwrap_err();
+        assert_eq!(err.to_string(), "expected number, found string");
+        let err = String::try_from(Value::Null).unwrap_err();
+        assert_eq!(err.to_string(), "expected string, found null");
+    }
+
+    #[test]
+    fn iterate_array() {
+        let v = crate::parse("[1,2,3]");
+        let mut sum = 0.0;
+        for n in &v {
+            sum += f64::try_from(n.clone()).unwrap();
+        }
+        assert_eq!(sum, 6.0);
+        assert_eq!((&v).into_iter().count(), 3);
+
+        let obj = crate::parse(r#"{"a": 1}"#);
+        assert_eq!((&obj).into_iter().count(), 0);
+        assert_eq!(
+            obj.entries().unwrap().collect::<Vec<_>>(),
+            [("a", &Value::Number(1.0))]
+        );
+        assert!(v.entries().is_none());
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(Value::Number(10.0).as_i64(), Some(10));
+        assert_eq!(Value::Number(10.5).as_i64(), None);
+        assert_eq!(Value::Number(1e19).as_i64(), None);
+        assert_eq!(
+            Value::Number(1e19).as_u64(),
+            Some(10_000_000_000_000_000_000)
+        );
+        assert_eq!(Value::Number(-1.0).as_i64(), Some(-1));
+        assert_eq!(Value::Number(-1.0).as_u64(), None);
+        assert_eq!(Value::Number(-0.0).as_u64(), Some(0));
+        assert_eq!(Value::Number(i64::MIN as f64).as_i64(), Some(i64::MIN));
+        // Rounds up to 2^63.
+        assert_eq!(Value::Number(i64::MAX as f64).as_i64(), None);
+        assert_eq!(Value::Number(f64::NAN).as_i64(), None);
+        assert_eq!(Value::Number(f64::INFINITY).as_u64(), None);
+        assert_eq!(Value::String("1".to_string()).as_i64(), None);
+
+        // Raw numbers keep digits beyond 2^53.
+        let raw = |s: &str| Value::RawNumber(s.to_string());
+        assert_eq!(
+            raw("9007199254740993").as_i64(),
+            Some(9_007_199_254_740_993)
+        );
+        assert_eq!(raw("18446744073709551615").as_u64(), Some(u64::MAX));
+        assert_eq!(raw("18446744073709551615").as_i64(), None);
+        assert_eq!(raw("1.5e1").as_u64(), Some(15));
+        assert_eq!(raw("1.5").as_u64(), None);
+    }
+
+    #[test]
+    fn push_and_insert() {
+        let mut arr = Value::Array(Vec::new());
+        arr.push(Value::Null).unwrap();
+        assert_eq!(arr, Value::from("[null]"));
+
+        let mut obj = Value::from(r#"{"a": 1}"#);
+        assert_eq!(
+            obj.push(Value::Null),
+            Err(Error::TypeMismatch {
+                expected: "array",
+                found: "object"
+            })
+        );
+        assert_eq!(
+            obj.insert("a".to_string(), Value::Null),
+            Ok(Some(Value::Number(1.0)))
+        );
+        assert_eq!(obj.insert("b".to_string(), Value::Null), Ok(None));
+        assert_eq!(obj, Value::from(r#"{"a": null, "b": null}"#));
+
+        let err = arr.insert("a".to_string(), Value::Null).unwrap_err();
+        assert_eq!(err.to_string(), "expected object, found array");
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("{}".parse::<Value>(), Ok(Value::Object(Map::new())));
+        let err = "x".parse::<Value>().unwrap_err();
+        assert_eq!(err.message(), "Unexpected token: 'x'");
+    }
+
+    #[test]
+    fn keys_and_values() {
+        let doc = Value::from(r#"{"Image": {"Width": 800, "Height": 600, "Title": "x"}}"#);
+        let mut keys: Vec<&str> = doc["Image"].keys().unwrap().collect();
+        keys.sort();
+        assert_eq!(keys, ["Height", "Title", "Width"]);
+
+        let numbers = doc["Image"]
+            .values()
+            .unwrap()
+            .filter(|v| matches!(v, Value::Number(_)))
+            .count();
+        assert_eq!(numbers, 2);
+
+        assert!(Value::from("[]").keys().is_none());
+        assert!(Value::Null.values().is_none());
+    }
+
+    #[test]
+    fn sizes() {
+        let doc = Value::from(r#"{"IDs": [], "Title": "x", "Tags": [1, 2]}"#);
+        assert_eq!(doc.len(), Some(3));
+        assert_eq!(doc["IDs"].len(), Some(0));
+        assert!(doc["IDs"].is_empty());
+        assert_eq!(doc["Tags"].len(), Some(2));
+        assert_eq!(doc["Title"].len(), None);
+        assert!(!doc["Title"].is_empty());
+
+        assert!(doc.contains_key("IDs"));
+        assert!(!doc.contains_key("ids"));
+        assert!(!doc["Tags"].contains_key("0"));
+    }
+
+    #[test]
+    fn take_hoists_subtree() {
+        let mut doc = Value::from(r#"{"outer": {"inner": {"a": 1}}}"#);
+        let inner = doc.pointer_mut("/outer/inner").unwrap().take();
+        assert_eq!(doc, Value::from(r#"{"outer": {"inner": null}}"#));
+        *doc.pointer_mut("/outer").unwrap() = inner;
+        assert_eq!(doc, Value::from(r#"{"outer": {"a": 1}}"#));
+        assert_eq!(Value::default(), Value::Null);
+    }
+
+    #[test]
+    fn entry_counts_words() {
+        let mut counts = Value::from("{}");
+        for word in ["a", "b", "a", "a"] {
+            let count = counts.entry(word).unwrap().or_insert(Value::Number(0.0));
+            if let Value::Number(n) = count {
+                *n += 1.0;
+            }
+        }
+        assert_eq!(counts, Value::from(r#"{"a": 3, "b": 1}"#));
+        assert!(Value::from("[]").entry("a").is_none());
+    }
+
+    #[test]
+    fn as_str() {
+        assert_eq!(Value::from(r#""a\nb""#).as_str(), Some("a\nb"));
+        assert_eq!(Value::from("1").as_str(), None);
+    }
+
+    #[test]
+    fn into_containers() {
+        let arr = Value::from("[1, 2]").into_array().unwrap();
+        assert_eq!(arr, vec![Value::Number(1.0), Value::Number(2.0)]);
+        assert_eq!(Value::from("{}").into_array(), None);
+
+        let obj = Value::from(r#"{"a": null}"#).into_object().unwrap();
+        assert_eq!(obj.get("a"), Some(&Value::Null));
+        assert_eq!(Value::from("[]").into_object(), None);
+
+        let s = Value::from(r#""abc""#).into_string().unwrap();
+        assert_eq!(s, "abc");
+        assert_eq!(Value::Null.into_string(), None);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::{Value, pointer::escape_token};
+
+impl Value {
+    /// Walks the tree depth-first and calls `f` on every node, children
+    /// before their parent, letting it rewrite nodes in place.
+    ///
+    /// Because a parent is visited last, `f` sees its children already
+    /// rewritten, and nodes that `f` itself creates are not walked again.
+    pub fn transform<F: FnMut(&mut Value)>(&mut self, mut f: F) {
+        transform_node(self, &mut f);
+    }
+
+    /// Walks the tree depth-first and folds every node into an accumulator,
+    /// parents before their children and members in document order.
+    pub fn fold<B, F: FnMut(B, &Value) -> B>(&self, init: B, mut f: F) -> B {
+        fold_node(self, init, &mut f)
+    }
+
+    /// Number of values in the tree, counting this one, every container and
+    /// every scalar. Object keys are not counted.
+    pub fn node_count(&self) -> usize {
+        match self {
+            Value::Object(obj) => 1 + obj.values().map(Value::node_count).sum::<usize>(),
+            Value::Array(arr) => 1 + arr.iter().map(Value::node_count).sum::<usize>(),
+            _ => 1,
+        }
+    }
+
+    /// Nesting depth of the tree, where scalars and empty containers have
+    /// depth one and each level of nesting adds one.
+    pub fn max_depth(&self) -> usize {
+        let children = match self {
+            Value::Object(obj) => obj.values().map(Value::max_depth).max(),
+            Value::Array(arr) => arr.iter().map(Value::max_depth).max(),
+            _ => None,
+        };
+        1 + children.unwrap_or(0)
+    }
+
+    /// Iterates over the JSON Pointer of every leaf in the tree together with
+    /// the value there, parents before children and object members in
+    /// document order. Leaves are scalars and empty arrays or objects; with
+    /// `include_containers`, non-empty containers, including the root, are
+    /// yielded as well.
+    pub fn pointers(&self, include_containers: bool) -> impl Iterator<Item = (String, &Value)> {
+        let mut stack = vec![(String::new(), self)];
+        std::iter::from_fn(move || {
+            loop {
+                let (ptr, node) = stack.pop()?;
+                let start = stack.len();
+                match node {
+                    Value::Object(obj) => {
+                        for (k, v) in obj {
+                            stack.push((format!("{ptr}/{}", escape_token(k)), v));
+                        }
+                    }
+                    Value::Array(arr) => {
+                        for (i, v) in arr.iter().enumerate() {
+                            stack.push((format!("{ptr}/{i}"), v));
+                        }
+                    }
+                    _ => {}
+                }
+                // Pop children in document order.
+                stack[start..].reverse();
+                if include_containers || node.len().unwrap_or(0) == 0 {
+                    return Some((ptr, node));
+                }
+            }
+        })
+    }
+
+    /// Recursively removes object members whose value is `null`. With
+    /// `remove_empty`, members that are (or become) empty arrays or objects
+    /// are removed too. Array elements are never removed.
+    pub fn prune_nulls(&mut self, remove_empty: bool) {
+        self.transform(|node| {
+            if let Value::Object(obj) = node {
+                obj.retain(|_, v| !(v == &Value::Null || remove_empty && v.is_empty()));
+            }
+        });
+    }
+}
+
+fn transform_node<F: FnMut(&mut Value)>(node: &mut Value, f: &mut F) {
+    match node {
+        Value::Object(obj) => obj.values_mut().for_each(|v| transform_node(v, f)),
+        Value::Array(arr) => arr.iter_mut().for_each(|v| transform_node(v, f)),
+        _ => {}
+    }
+    f(node);
+}
+
+fn fold_node<B, F: FnMut(B, &Value) -> B>(node: &Value, acc: B, f: &mut F) -> B {
+    let acc = f(acc, node);
+    match node {
+        Value::Object(obj) => obj.values().fold(acc, |acc, v| fold_node(v, acc, f)),
+        Value::Array(arr) => arr.iter().fold(acc, |acc, v| fold_node(v, acc, f)),
+        _ => acc,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transform_increments_numbers() {
+        let mut v = Value::from(r#"{"a": 1, "b": [2, {"c": 3}, "x"], "d": null}"#);
+        v.transform(|node| {
+            if let Value::Number(n) = node {
+                *n += 1.0;
+            }
+        });
+        assert_eq!(
+            v,
+            Value::from(r#"{"a": 2, "b": [3, {"c": 4}, "x"], "d": null}"#)
+        );
+    }
+
+    #[test]
+    fn prune_nulls_in_nested_objects() {
+        let mut v = Value::from(r#"{"a":null,"b":{"c":null,"d":1}}"#);
+        v.prune_nulls(false);
+        assert_eq!(v, Value::from(r#"{"b":{"d":1}}"#));
+
+        let mut v = Value::from(r#"{"a": [null, {"b": null}], "c": {"d": null}, "e": ""}"#);
+        let mut kept = v.clone();
+        kept.prune_nulls(false);
+        assert_eq!(kept, Value::from(r#"{"a": [null, {}], "c": {}, "e": ""}"#));
+        v.prune_nulls(true);
+        assert_eq!(v, Value::from(r#"{"a": [null, {}], "e": ""}"#));
+    }
+
+    #[test]
+    fn transform_visits_children_first() {
+        let mut v = Value::from(r#"{"User": {"Password": "x", "Name": "y"}}"#);
+        let mut visited = Vec::new();
+        v.transform(|node| {
+            if let Value::Object(obj) = node {
+                if let Some(password) = obj.get_mut("Password") {
+                    *password = Value::String("***".to_string());
+                }
+                *obj = obj.drain().map(|(k, v)| (k.to_lowercase(), v)).collect();
+            }
+            visited.push(node.type_name());
+        });
+        assert_eq!(
+            v,
+            Value::from(r#"{"user": {"password": "***", "name": "y"}}"#)
+        );
+        assert_eq!(visited.last(), Some(&"object"));
+        assert_eq!(visited.len(), 4);
+    }
+
+    #[test]
+    fn fold_sums_numbers() {
+        let v = Value::from(r#"{"a":[1,2],"b":3}"#);
+        let sum = v.fold(0.0, |sum, node| match node {
+            Value::Number(n) => sum + n,
+            _ => sum,
+        });
+        assert_eq!(sum, 6.0);
+
+        let v = Value::from(r#"["x", {"y": "z"}, 1]"#);
+        let strings = v.fold(Vec::new(), |mut acc, node| {
+            if let Value::String(s) = node {
+                acc.push(s.clone());
+            }
+            acc
+        });
+        assert_eq!(strings, ["x", "z"]);
+        assert_eq!(v.fold(0, |n, _| n + 1), v.node_count());
+    }
+
+    #[test]
+    fn node_count_and_depth_of_rfc_example() {
+        let v = Value::from(
+            r#"{
+                "Image": {
+                    "Width":  800,
+                    "Height": 600,
+                    "Title":  "View from 15th Floor",
+                    "Thumbnail": {
+                        "Url":    "http://www.example.com/image/481989943",
+                        "Height": 125,
+                        "Width":  100
+                    },
+                    "Animated" : false,
+                    "IDs": [116, 943, 234, 38793]
+                }
+            }"#,
+        );
+        // Root, Image, its 5 scalars, Thumbnail and its 3, IDs and its 4.
+        assert_eq!(v.node_count(), 15);
+        // Root > Image > IDs > 116.
+        assert_eq!(v.max_depth(), 4);
+
+        assert_eq!(Value::Null.node_count(), 1);
+        assert_eq!(Value::Null.max_depth(), 1);
+        assert_eq!(Value::from("[]").max_depth(), 1);
+        assert_eq!(Value::from("[[]]").max_depth(), 2);
+    }
+
+    #[test]
+    fn leaf_pointers() {
+        let v = Value::from(r#"{"a":[1,2]}"#);
+        let ptrs: Vec<String> = v.pointers(false).map(|(ptr, _)| ptr).collect();
+        assert_eq!(ptrs, ["/a/0", "/a/1"]);
+
+        let v = Value::from(r#"{"b": {"x/y": true, "e": []}, "a": [null]}"#);
+        let all: Vec<(String, &Value)> = v.pointers(true).collect();
+        let ptrs: Vec<&str> = all.iter().map(|(ptr, _)| ptr.as_str()).collect();
+        assert_eq!(ptrs, ["", "/b", "/b/x~1y", "/b/e", "/a", "/a/0"]);
+        for (ptr, node) in all {
+            assert_eq!(v.pointer(&ptr), Some(node));
+        }
+
+        let scalar = Value::Number(1.0);
+        assert_eq!(
+            scalar.pointers(false).collect::<Vec<_>>(),
+            [(String::new(), &scalar)]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Conformance against a curated subset of JSONTestSuite
+//! (<https://github.com/nst/JSONTestSuite>, MIT licensed), kept in
+//! `tests/JSONTestSuite` under the original file names.
+//!
+//! Files starting with `y_` must be accepted and `n_` files rejected. For
+//! `i_` files RFC 8259 leaves the outcome to the implementation, so the
+//! table below records what rj does with each of them.
+
+use std::{fs, path::PathBuf};
+
+/// Whether rj accepts each implementation-defined file, and why.
+const IMPLEMENTATION_DEFINED: &[(&str, bool)] = &[
+    // Numbers outside the range of `f64` become infinity or zero, and large
+    // integers lose precision; `ParseOptions` can reject or keep them.
+    ("i_number_double_huge_neg_exp.json", true),
+    ("i_number_real_neg_overflow.json", true),
+    ("i_number_real_pos_overflow.json", true),
+    ("i_number_real_underflow.json", true),
+    ("i_number_too_big_neg_int.json", true),
+    ("i_number_too_big_pos_int.json", true),
+    ("i_number_very_big_negative_int.json", true),
+    // A `String` cannot hold an unpaired surrogate.
+    ("i_object_key_lone_2nd_surrogate.json", false),
+    ("i_string_1st_surrogate_but_2nd_missing.json", false),
+    ("i_string_1st_valid_surrogate_2nd_invalid.json", false),
+    ("i_string_incomplete_surrogate_and_escape_valid.json", false),
+    ("i_string_invalid_lonely_surrogate.json", false),
+    ("i_string_lone_second_surrogate.json", false),
+    // Input must be UTF-8; a leading UTF-8 byte order mark is skipped.
+    ("i_string_invalid_utf-8.json", false),
+    ("i_string_not_in_unicode_range.json", false),
+    ("i_string_UTF-16LE_with_BOM.json", false),
+    ("i_structure_UTF-8_BOM_empty_object.json", true),
+    // Nesting is limited to `ParseOptions::max_depth`, 128 by default.
+    ("i_structure_500_nested_arrays.json", false),
+];
+
+/// The suite files whose names start with `prefix`, with their contents.
+fn files(prefix: &str) -> Vec<(String, Vec<u8>)> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/JSONTestSuite");
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?.to_string();
+            name.starts_with(prefix)
+                .then(|| (name, fs::read(&path).unwrap()))
+        })
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "no {prefix} files");
+    files
+}
+
+#[test]
+fn accepts_valid_documents() {
+    for (name, input) in files("y_") {
+        if let Err(e) = rj::parse_bytes(&input) {
+            panic!("{name}: {e}");
+        }
+    }
+}
+
+#[test]
+fn rejects_invalid_documents() {
+    for (name, input) in files("n_") {
+        if let Ok(v) = rj::parse_bytes(&input) {
+            panic!("{name}: parsed as {v:?}");
+        }
+    }
+}
+
+#[test]
+fn implementation_defined_documents() {
+    let files = files("i_");
+    assert_eq!(files.len(), IMPLEMENTATION_DEFINED.len());
+    for (name, input) in files {
+        let (_, accepted) = IMPLEMENTATION_DEFINED
+            .iter()
+            .find(|(n, _)| *n == name)
+            .unwrap_or_else(|| panic!("{name} is not documented"));
+        assert_eq!(rj::parse_bytes(&input).is_ok(), *accepted, "{name}");
+    }
+}
+
+#[test]
+fn valid_documents_round_trip() {
+    for (name, input) in files("y_") {
+        let v = rj::parse_bytes(&input).unwrap();
+        assert_eq!(rj::try_parse(&v.to_compact_string()), Ok(v), "{name}");
+    }
+}